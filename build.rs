@@ -1,10 +1,10 @@
-//! Transforms `src/claims.yml` into a function.
+//! Validates `src/fields.toml` and transforms it into functions.
 
 use linked_hash_map::LinkedHashMap;
 use serde::Deserialize;
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     env,
     error::Error,
     fmt,
@@ -20,6 +20,17 @@ struct Field {
     link: Option<String>,
 }
 
+impl Field {
+    fn validate(&self, context: &str, errors: &mut Vec<String>) {
+        if let Some(link) = &self.link {
+            if let Err(err) = check_link(link) {
+                errors.push(format!("{context}: invalid link `{link}`: {err}"));
+            }
+        }
+        check_html_field(context, "description", &self.description, errors);
+    }
+}
+
 impl fmt::Display for Field {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -32,20 +43,55 @@ impl fmt::Display for Field {
     }
 }
 
+/// Machine-checkable claim format rules and the corresponding `FormatRule` variants.
+const FORMAT_RULES: &[(&str, &str)] = &[
+    ("string", "String"),
+    ("uri", "Uri"),
+    ("bool", "Bool"),
+    ("phone", "Phone"),
+    ("email", "Email"),
+    ("locale", "Locale"),
+    ("zoneinfo", "Zoneinfo"),
+    ("date", "Date"),
+    ("space_list", "SpaceList"),
+    ("timestamp", "Timestamp"),
+    ("string_or_array", "StringOrArray"),
+    ("string_array", "StringArray"),
+    ("object", "Object"),
+];
+
+fn format_rule_variant(rule: &str) -> Option<&'static str> {
+    FORMAT_RULES
+        .iter()
+        .find_map(|&(name, variant)| (name == rule).then_some(variant))
+}
+
 #[derive(Debug, Deserialize)]
 struct Claim {
     #[serde(flatten)]
     field: Field,
     category: String,
+    format: Option<String>,
+    format_rule: Option<String>,
 }
 
 impl fmt::Display for Claim {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format_rule = self
+            .format_rule
+            .as_deref()
+            .and_then(format_rule_variant)
+            .map_or_else(
+                || "None".to_owned(),
+                |variant| format!("Some(FormatRule::{variant})"),
+            );
         write!(
             formatter,
-            "{{ field: {field}, category: {cat:?} }}",
+            "{{ field: {field}, category: {cat:?}, format: {format:?}, \
+             format_rule: {format_rule} }}",
             field = self.field,
-            cat = self.category
+            cat = self.category,
+            format = self.format.as_deref().map(str::trim)
         )
     }
 }
@@ -53,21 +99,248 @@ impl fmt::Display for Claim {
 #[derive(Debug, Deserialize)]
 struct ClaimCategory {
     title: String,
+    description: String,
 }
 
 impl fmt::Display for ClaimCategory {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(formatter, " {{ title: {title:?} }}", title = self.title)
+        write!(
+            formatter,
+            " {{ title: {title:?}, description: {descr:?} }}",
+            title = self.title,
+            descr = self.description.trim()
+        )
+    }
+}
+
+/// Claim category for claims not described in `fields.toml`. It is used by the app and is expected
+/// to have no claims.
+const FALLBACK_CATEGORY: &str = "unknown";
+
+/// HTML elements that have no closing tag.
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "wbr"];
+
+/// Checks that the link is a well-formed HTTPS URL.
+fn check_link(link: &str) -> Result<(), String> {
+    let Some(rest) = link.strip_prefix("https://") else {
+        return Err("link must use HTTPS".to_owned());
+    };
+    if let Some(ch) = rest.chars().find(|&ch| {
+        ch.is_whitespace() || ch.is_control() || matches!(ch, '"' | '\'' | '<' | '>' | '\\' | '`')
+    }) {
+        return Err(format!("link contains disallowed char {ch:?}"));
+    }
+
+    let host_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let host = &rest[..host_end];
+    let (domain, port) = match host.split_once(':') {
+        Some((domain, port)) => (domain, Some(port)),
+        None => (host, None),
+    };
+    let labels: Vec<_> = domain.split('.').collect();
+    let is_valid_domain = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+        });
+    let is_valid_port = port.map_or(true, |port| port.parse::<u16>().is_ok());
+    if is_valid_domain && is_valid_port {
+        Ok(())
+    } else {
+        Err(format!("invalid host `{host}`"))
     }
 }
 
+/// Checks that tags in an HTML snippet are balanced, and that all links in it are valid.
+fn check_html(html: &str) -> Result<(), String> {
+    let mut open_tags = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        let Some(len) = rest[start..].find('>') else {
+            return Err(format!("unterminated tag at `{}`", &rest[start..]));
+        };
+        let tag = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim().to_ascii_lowercase();
+            match open_tags.pop() {
+                Some(open) if open == name => { /* tags are balanced */ }
+                Some(open) => return Err(format!("`</{name}>` closes `<{open}>`")),
+                None => return Err(format!("`</{name}>` has no matching opening tag")),
+            }
+            continue;
+        }
+
+        let (tag, is_self_closing) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = name.to_ascii_lowercase();
+        if name.is_empty() || !name.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+            return Err(format!("invalid tag `<{tag}>`"));
+        }
+        if name == "a" {
+            let href = attrs
+                .split_once("href=")
+                .and_then(|(_, value)| {
+                    let quote = value.chars().next().filter(|ch| matches!(ch, '"' | '\''))?;
+                    value[1..].split_once(quote).map(|(href, _)| href)
+                })
+                .ok_or_else(|| format!("`<{tag}>` has no quoted `href` attribute"))?;
+            check_link(href).map_err(|err| format!("invalid link `{href}`: {err}"))?;
+        }
+        if !is_self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+            open_tags.push(name);
+        }
+    }
+
+    if let Some(open) = open_tags.pop() {
+        Err(format!("`<{open}>` is not closed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn check_html_field(context: &str, field_name: &str, html: &str, errors: &mut Vec<String>) {
+    if let Err(err) = check_html(html) {
+        errors.push(format!("{context}: invalid HTML in `{field_name}`: {err}"));
+    }
+}
+
+/// Checks that human-readable names are unique.
+fn check_unique_names<'a>(
+    kind: &str,
+    names: impl Iterator<Item = (&'a str, &'a str)>,
+    errors: &mut Vec<String>,
+) {
+    let mut keys_by_name = HashMap::<_, &str>::new();
+    for (key, name) in names {
+        if let Some(prev_key) = keys_by_name.insert(name, key) {
+            errors.push(format!(
+                "{kind} `{prev_key}` and `{key}` have the same name `{name}`"
+            ));
+        }
+    }
+}
+
+/// Errors produced by [`StandardFields::validate()`].
+struct ValidationErrors(Vec<String>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "invalid `src/fields.toml`:")?;
+        for err in &self.0 {
+            write!(formatter, "\n  - {err}")?;
+        }
+        Ok(())
+    }
+}
+
+// `Debug` is used to output errors returned from `main()`, so it is made human-readable.
+impl fmt::Debug for ValidationErrors {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, formatter)
+    }
+}
+
+impl Error for ValidationErrors {}
+
 #[derive(Debug, Deserialize)]
 struct StandardFields {
     standard_headers: HashMap<String, Field>,
-    standard_claims: HashMap<String, Claim>,
+    standard_claims: LinkedHashMap<String, Claim>,
     claims_categories: LinkedHashMap<String, ClaimCategory>,
 }
 
+impl StandardFields {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = vec![];
+
+        let mut headers: Vec<_> = self.standard_headers.iter().collect();
+        headers.sort_unstable_by_key(|(name, _)| name.as_str());
+        for (name, header) in &headers {
+            header.validate(&format!("header `{name}`"), &mut errors);
+        }
+        check_unique_names(
+            "headers",
+            headers
+                .iter()
+                .map(|(key, header)| (key.as_str(), header.name.as_str())),
+            &mut errors,
+        );
+
+        for (name, claim) in &self.standard_claims {
+            let context = format!("claim `{name}`");
+            claim.field.validate(&context, &mut errors);
+            if !self.claims_categories.contains_key(&claim.category) {
+                errors.push(format!("{context}: unknown category `{}`", claim.category));
+            }
+            match &claim.format_rule {
+                Some(rule) if format_rule_variant(rule).is_none() => {
+                    errors.push(format!("{context}: unknown format rule `{rule}`"));
+                }
+                None if claim.format.is_some() => {
+                    errors.push(format!(
+                        "{context}: `format` is specified without `format_rule`"
+                    ));
+                }
+                _ => { /* OK */ }
+            }
+            if let Some(format) = &claim.format {
+                check_html_field(&context, "format", format, &mut errors);
+            }
+        }
+        check_unique_names(
+            "claims",
+            self.standard_claims
+                .iter()
+                .map(|(key, claim)| (key.as_str(), claim.field.name.as_str())),
+            &mut errors,
+        );
+
+        for (id, category) in &self.claims_categories {
+            let context = format!("category `{id}`");
+            check_html_field(&context, "description", &category.description, &mut errors);
+        }
+        check_unique_names(
+            "categories",
+            self.claims_categories
+                .iter()
+                .map(|(key, category)| (key.as_str(), category.title.as_str())),
+            &mut errors,
+        );
+        if !self.claims_categories.contains_key(FALLBACK_CATEGORY) {
+            errors.push(format!(
+                "fallback category `{FALLBACK_CATEGORY}` is not defined"
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+
+    /// Emits warnings for categories without claims.
+    fn warn_on_empty_categories(&self) {
+        let used_categories: HashSet<_> = self
+            .standard_claims
+            .values()
+            .map(|claim| claim.category.as_str())
+            .collect();
+        for id in self.claims_categories.keys() {
+            if id != FALLBACK_CATEGORY && !used_categories.contains(id.as_str()) {
+                println!("cargo:warning=claim category `{id}` in `src/fields.toml` has no claims");
+            }
+        }
+    }
+}
+
 fn generate_map_fn<T: fmt::Display>(
     dest_file: &mut File,
     fn_name: &str,
@@ -129,6 +402,8 @@ fn generate_slice_fn<T: fmt::Display>(
 fn main() -> Result<(), Box<dyn Error>> {
     let src_file = fs::read_to_string("src/fields.toml")?;
     let fields: StandardFields = toml::from_str(&src_file)?;
+    fields.validate()?;
+    fields.warn_on_empty_categories();
 
     let out_dir = env::var_os("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("std_maps.rs");
@@ -140,9 +415,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         "StandardHeader",
         &fields.standard_headers,
     )?;
-    generate_map_fn(
+    generate_slice_fn(
         &mut dest_file,
-        "create_claims_map",
+        "create_claims",
         "StandardClaim",
         &fields.standard_claims,
     )?;
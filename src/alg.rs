@@ -0,0 +1,262 @@
+//! `ES384` and `ES512` algorithms, which are not provided by `jwt-compact` out of the box.
+//!
+//! Both algorithms use ECDSA on the corresponding NIST curves (P-384 and P-521) with the digest
+//! function mandated by [RFC 7518] (SHA-384 and SHA-512, respectively).
+//!
+//! [RFC 7518]: https://www.rfc-editor.org/rfc/rfc7518.html#section-3.4
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use jwt_compact::{
+    alg::SecretBytes,
+    jwk::{JsonWebKey, JwkError, KeyType},
+    Algorithm, AlgorithmSignature,
+};
+
+use std::{borrow::Cow, fmt, num::NonZeroUsize};
+
+fn ensure_curve(curve: &str, expected: &str) -> Result<(), JwkError> {
+    if curve == expected {
+        Ok(())
+    } else {
+        Err(JwkError::UnexpectedValue {
+            field: "crv".to_owned(),
+            expected: expected.to_owned(),
+            actual: curve.to_owned(),
+        })
+    }
+}
+
+fn ensure_len(field: &str, bytes: &[u8], expected_len: usize) -> Result<(), JwkError> {
+    if bytes.len() == expected_len {
+        Ok(())
+    } else {
+        Err(JwkError::UnexpectedLen {
+            field: field.to_owned(),
+            expected: expected_len,
+            actual: bytes.len(),
+        })
+    }
+}
+
+/// Defines an ECDSA algorithm together with its public key and signature newtypes.
+/// Newtypes are necessary because of orphan rules: `AlgorithmSignature` and conversions
+/// from / to `JsonWebKey` are foreign traits.
+macro_rules! ecdsa_algorithm {
+    (
+        $(#[$meta:meta])*
+        $alg:ident {
+            name: $name:expr,
+            curve: $curve:expr,
+            crate: $krate:ident,
+            digest: $digest:ty,
+            coordinate_size: $coord_size:expr,
+            public_key: $public_key:ident,
+            signature: $signature:ident,
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct $alg;
+
+        impl $alg {
+            /// Curve name as used in the `crv` field of JWKs.
+            pub const CURVE: &'static str = $curve;
+            /// Byte size of a serialized field element (e.g., a point coordinate).
+            pub const COORDINATE_SIZE: usize = $coord_size;
+
+            /// Parses a signing key from a JWK. The JWK must contain the secret scalar (`d`)
+            /// and the matching public point.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the JWK is invalid or does not contain the secret scalar.
+            pub fn signing_key_from_jwk(
+                jwk: &JsonWebKey<'_>,
+            ) -> Result<$krate::ecdsa::SigningKey, JwkError> {
+                let public_key = $public_key::try_from(jwk)?;
+                let JsonWebKey::EllipticCurve { secret, .. } = jwk else {
+                    unreachable!("checked when converting to a public key");
+                };
+                let secret = secret
+                    .as_deref()
+                    .ok_or_else(|| JwkError::NoField("d".to_owned()))?;
+                ensure_len("d", secret, Self::COORDINATE_SIZE)?;
+
+                let signing_key = $krate::ecdsa::SigningKey::from_slice(secret)
+                    .map_err(|err| JwkError::custom(anyhow::anyhow!(err)))?;
+                if $public_key::from(&signing_key) == public_key {
+                    Ok(signing_key)
+                } else {
+                    Err(JwkError::MismatchedKeys)
+                }
+            }
+
+            /// Converts a signing key to a JWK. The JWK contains both the secret scalar
+            /// and the public point.
+            pub fn signing_key_to_jwk(
+                signing_key: &$krate::ecdsa::SigningKey,
+            ) -> JsonWebKey<'static> {
+                let mut jwk = JsonWebKey::from(&$public_key::from(signing_key));
+                if let JsonWebKey::EllipticCurve { secret, .. } = &mut jwk {
+                    let secret_bytes = signing_key.to_bytes();
+                    *secret = Some(SecretBytes::owned(secret_bytes.to_vec()));
+                }
+                jwk
+            }
+        }
+
+        #[doc = concat!("Public key for the `", $name, "` algorithm.")]
+        #[derive(Clone)]
+        pub struct $public_key($krate::ecdsa::VerifyingKey);
+
+        impl fmt::Debug for $public_key {
+            fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let point = self.0.to_encoded_point(true);
+                formatter
+                    .debug_tuple(stringify!($public_key))
+                    .field(&Base64UrlUnpadded::encode_string(point.as_bytes()))
+                    .finish()
+            }
+        }
+
+        impl PartialEq for $public_key {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_encoded_point(true) == other.0.to_encoded_point(true)
+            }
+        }
+
+        impl $public_key {
+            /// Creates a key from the SEC1 presentation (compressed or uncompressed).
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the bytes do not represent a valid curve point.
+            pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, JwkError> {
+                $krate::ecdsa::VerifyingKey::from_sec1_bytes(bytes)
+                    .map(Self)
+                    .map_err(|err| JwkError::custom(anyhow::anyhow!(err)))
+            }
+        }
+
+        impl From<&$krate::ecdsa::SigningKey> for $public_key {
+            fn from(signing_key: &$krate::ecdsa::SigningKey) -> Self {
+                Self($krate::ecdsa::VerifyingKey::from(signing_key))
+            }
+        }
+
+        impl TryFrom<&JsonWebKey<'_>> for $public_key {
+            type Error = JwkError;
+
+            fn try_from(jwk: &JsonWebKey<'_>) -> Result<Self, Self::Error> {
+                let JsonWebKey::EllipticCurve { curve, x, y, .. } = jwk else {
+                    return Err(JwkError::UnexpectedKeyType {
+                        expected: KeyType::EllipticCurve,
+                        actual: jwk.key_type(),
+                    });
+                };
+                ensure_curve(curve, $alg::CURVE)?;
+                ensure_len("x", x, $alg::COORDINATE_SIZE)?;
+                ensure_len("y", y, $alg::COORDINATE_SIZE)?;
+
+                let mut key_bytes = Vec::with_capacity(2 * $alg::COORDINATE_SIZE + 1);
+                key_bytes.push(4); // uncompressed key marker
+                key_bytes.extend_from_slice(x);
+                key_bytes.extend_from_slice(y);
+                Self::from_sec1_bytes(&key_bytes)
+            }
+        }
+
+        impl From<&$public_key> for JsonWebKey<'static> {
+            fn from(key: &$public_key) -> Self {
+                let uncompressed = key.0.to_encoded_point(false);
+                JsonWebKey::EllipticCurve {
+                    curve: Cow::Borrowed($alg::CURVE),
+                    x: Cow::Owned(uncompressed.x().expect("x coordinate").to_vec()),
+                    y: Cow::Owned(uncompressed.y().expect("y coordinate").to_vec()),
+                    secret: None,
+                }
+            }
+        }
+
+        #[doc = concat!("Signature produced by the `", $name, "` algorithm.")]
+        #[derive(Debug, Clone)]
+        pub struct $signature($krate::ecdsa::Signature);
+
+        impl AlgorithmSignature for $signature {
+            const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(2 * $alg::COORDINATE_SIZE);
+
+            fn try_from_slice(slice: &[u8]) -> anyhow::Result<Self> {
+                $krate::ecdsa::Signature::from_slice(slice)
+                    .map(Self)
+                    .map_err(|err| anyhow::anyhow!(err))
+            }
+
+            fn as_bytes(&self) -> Cow<'_, [u8]> {
+                Cow::Owned(self.0.to_bytes().to_vec())
+            }
+        }
+
+        impl Algorithm for $alg {
+            type SigningKey = $krate::ecdsa::SigningKey;
+            type VerifyingKey = $public_key;
+            type Signature = $signature;
+
+            fn name(&self) -> Cow<'static, str> {
+                Cow::Borrowed($name)
+            }
+
+            fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
+                use sha2::Digest as _;
+                use $krate::ecdsa::signature::hazmat::PrehashSigner as _;
+
+                let digest = <$digest>::digest(message);
+                let signature = signing_key
+                    .sign_prehash(&digest)
+                    .expect("cannot sign message digest");
+                $signature(signature)
+            }
+
+            fn verify_signature(
+                &self,
+                signature: &Self::Signature,
+                verifying_key: &Self::VerifyingKey,
+                message: &[u8],
+            ) -> bool {
+                use sha2::Digest as _;
+                use $krate::ecdsa::signature::hazmat::PrehashVerifier as _;
+
+                let digest = <$digest>::digest(message);
+                verifying_key
+                    .0
+                    .verify_prehash(&digest, &signature.0)
+                    .is_ok()
+            }
+        }
+    };
+}
+
+ecdsa_algorithm!(
+    /// `ES384` signing algorithm: ECDSA on the P-384 curve with SHA-384 digest.
+    Es384 {
+        name: "ES384",
+        curve: "P-384",
+        crate: p384,
+        digest: sha2::Sha384,
+        coordinate_size: 48,
+        public_key: P384PublicKey,
+        signature: Es384Signature,
+    }
+);
+
+ecdsa_algorithm!(
+    /// `ES512` signing algorithm: ECDSA on the P-521 curve with SHA-512 digest.
+    Es512 {
+        name: "ES512",
+        curve: "P-521",
+        crate: p521,
+        digest: sha2::Sha512,
+        coordinate_size: 66,
+        public_key: P521PublicKey,
+        signature: Es512Signature,
+    }
+);
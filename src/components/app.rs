@@ -1,19 +1,34 @@
 //! Root application component.
 
-use jwt_compact::{jwk::JsonWebKey, TimeOptions, UntrustedToken, ValidationError};
-use wasm_bindgen::UnwrapThrowExt;
-use yew::{html, html::Scope, virtual_dom::VList, Component, Context, Html, Properties};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
+use gloo_timers::callback::Timeout;
+use jwt_compact::{
+    jwk::JsonWebKey, Header, ParseError, Thumbprint, TimeOptions, UntrustedToken, ValidationError,
+};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement};
+use yew::{classes, html, html::Scope, virtual_dom::VList, Component, Context, Html, Properties};
 
 use std::fmt;
 
 use super::{
-    common::{str_to_html, view_data_row, Alert, ComponentRef, Icon},
-    key_input::{KeyInput, KeyInputMessage},
-    token_input::{TokenInput, TokenInputMessage},
+    common::{
+        str_to_html, view_code_snippet, view_data_row, view_wide_data_row, Alert, ComponentRef,
+        Icon,
+    },
+    key_generator::{GeneratedKeyPair, KeyGenerator},
+    key_input::{KeyInput, KeyInputFormat, KeyInputMessage, KeyUsage},
+    token_input::{InputToken, TokenInput, TokenInputMessage, TokenParseError},
+    token_signer::{TokenSigner, TokenSignerMessage},
 };
 use crate::{
+    detached::DetachedToken,
+    expectations::{ExpectationError, ExpectationField, Expectations},
     fields::{ClaimCategory, StandardClaim},
-    keys::{GenericClaims, GenericToken, KeyInstance},
+    jwe::{DecryptionError, EncryptedToken, JweHeader},
+    jws::JsonToken,
+    keys::{GenericClaims, KeyInstance, KeySet, KeySetEntry, SigningKeyInstance},
+    nested::{is_nested_content_type, NestingToken},
 };
 
 /// Result of token verification.
@@ -22,7 +37,14 @@ enum TokenResult {
     /// No sufficient inputs to verify the token.
     None,
     /// Token was verified successfully.
-    Ok(Box<GenericToken>),
+    Ok {
+        claims: Box<GenericClaims>,
+        /// Description of the key that has verified the token. Only set if it is not obvious
+        /// which key was used.
+        verifying_key: Option<String>,
+        /// Information about token decryption. Only set for encrypted tokens.
+        decryption: Option<DecryptionInfo>,
+    },
     Err {
         /// Error verifying the token.
         err: ExtendedValidationError,
@@ -31,10 +53,37 @@ enum TokenResult {
     },
 }
 
+/// Information about decryption of an encrypted token.
+#[derive(Debug)]
+struct DecryptionInfo {
+    /// Description of the key that has decrypted the token. Only set if it is not obvious
+    /// which key was used.
+    key: Option<String>,
+    /// Whether the decrypted payload is a signed token.
+    is_signed: bool,
+}
+
 #[derive(Debug)]
 enum ExtendedValidationError {
     Err(ValidationError),
+    Decryption(DecryptionError),
     NoKey,
+    NoDecryptionKey,
+    /// Token payload is detached and was not provided.
+    NoPayload,
+    /// Token is unsecured (uses the `none` algorithm); such tokens are never accepted.
+    Unsecured {
+        has_signature: bool,
+    },
+    /// Payload of a nested token cannot be parsed as a token.
+    MalformedNestedToken(TokenParseError),
+    /// Token header lists critical extensions (`crit`) not supported by the verifier.
+    UnsupportedCritical(Vec<String>),
+    /// Some signatures of a token in the JWS JSON serialization have failed verification.
+    InvalidSignatures {
+        failed: usize,
+        total: usize,
+    },
 }
 
 impl From<ValidationError> for ExtendedValidationError {
@@ -90,85 +139,988 @@ impl ExtendedValidationError {
         )
     }
 
+    fn view_decryption_err(err: &DecryptionError) -> Html {
+        let tip = match err {
+            DecryptionError::Authentication
+            | DecryptionError::KeyUnwrap
+            | DecryptionError::KeyTypeMismatch { .. }
+            | DecryptionError::NoPrivateKey => Some(
+                "Check that the key is appropriate for token decryption. For the <code>dir</code> \
+                 and <code>A*KW</code> algorithms, the key must be a symmetric key with \
+                 the appropriate length. For the <code>RSA-OAEP*</code> and <code>ECDH-ES*</code> \
+                 algorithms, the key must be the private key of the token recipient.",
+            ),
+            _ => None,
+        };
+
+        Alert::Danger.view(
+            "Error decrypting token",
+            html! {
+                <>
+                    <p class="card-text">{ err.to_string() }</p>
+                    { if let Some(tip) = tip {
+                        html! {
+                            <p class="card-text text-muted">
+                                <small>{ str_to_html(tip) }</small>
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </>
+            },
+        )
+    }
+
+    fn view_no_decryption_key_warning() -> Html {
+        Alert::Warning.view(
+            "Cannot decrypt token",
+            html! {
+                <p class="card-text">
+                    { "…since no valid decryption key is provided." }
+                </p>
+            },
+        )
+    }
+
+    fn view_unsecured_err(has_signature: bool) -> Html {
+        Alert::Danger.view(
+            "Unsecured token",
+            html! {
+                <>
+                    <p class="card-text">
+                        { "The token uses the " }<code>{ "none" }</code>
+                        { " algorithm, i.e., it is an " }
+                        <a href="https://www.rfc-editor.org/rfc/rfc7519.html#section-6">
+                            { "unsecured JWT" }
+                        </a>
+                        { " with no integrity protection. Anyone can create such a token or \
+                           modify its claims, so the claims cannot be trusted. Verification is \
+                           refused regardless of the provided key." }
+                    </p>
+                    { if has_signature {
+                        html! {
+                            <p class="card-text">
+                                { "Additionally, the token has a non-empty signature, which is \
+                                   invalid for unsecured tokens." }
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <p class="card-text text-muted">
+                        <small>
+                            { "Applications must never accept unsecured tokens in place of signed \
+                               ones. A verifier that takes the algorithm from the token header and \
+                               accepts " }
+                            <code>{ "none" }</code>
+                            { " lets an attacker forge tokens with arbitrary claims. Make sure \
+                               that the verifier only allows a fixed set of expected algorithms." }
+                        </small>
+                    </p>
+                </>
+            },
+        )
+    }
+
+    fn view_unsupported_critical_err(fields: &[String]) -> Html {
+        let fields = fields.iter().enumerate().map(|(i, field)| {
+            let separator = if i == 0 { "" } else { ", " };
+            html! { <>{ separator }<code>{ field }</code></> }
+        });
+        Alert::Danger.view(
+            "Unsupported critical header",
+            html! {
+                <>
+                    <p class="card-text">
+                        { "The token header lists extensions that must be understood \
+                           by the verifier, but are not supported: " }
+                        { for fields }
+                        { "." }
+                    </p>
+                    <p class="card-text text-muted">
+                        <small>
+                            { "As per " }
+                            <a href="https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.11">
+                                { "RFC 7515, section 4.1.11" }
+                            </a>
+                            { ", a token with unsupported critical extensions must be rejected." }
+                        </small>
+                    </p>
+                </>
+            },
+        )
+    }
+
     fn view(&self) -> Html {
         match self {
             Self::Err(err) => Self::view_err(err),
+            Self::Decryption(err) => Self::view_decryption_err(err),
             Self::NoKey => Self::view_no_key_warning(),
+            Self::NoDecryptionKey => Self::view_no_decryption_key_warning(),
+            Self::NoPayload => Alert::Warning.view(
+                "Cannot verify integrity",
+                html! {
+                    <p class="card-text">
+                        { "…since the token payload is detached and is not provided." }
+                    </p>
+                },
+            ),
+            Self::Unsecured { has_signature } => Self::view_unsecured_err(*has_signature),
+            Self::InvalidSignatures { failed, total } => Alert::Danger.view(
+                "Error verifying token",
+                html! {
+                    <p class="card-text">
+                        { format!("{failed} of {total} token signature(s) have failed verification.") }
+                    </p>
+                },
+            ),
+            Self::UnsupportedCritical(fields) => Self::view_unsupported_critical_err(fields),
+            Self::MalformedNestedToken(err) => Alert::Danger.view(
+                "Error parsing nested token",
+                html! { <p class="card-text">{ err.to_string() }</p> },
+            ),
+        }
+    }
+}
+
+/// Layer of a nested token. Layers are ordered from the outermost one.
+#[derive(Debug)]
+struct TokenLayer {
+    is_encrypted: bool,
+    algorithm: String,
+    encryption: Option<String>,
+    key_id: Option<String>,
+    content_type: Option<String>,
+    status: LayerStatus,
+}
+
+impl TokenLayer {
+    fn signed(
+        algorithm: &str,
+        header: &Header,
+        content_type: Option<&str>,
+        status: LayerStatus,
+    ) -> Self {
+        Self {
+            is_encrypted: false,
+            algorithm: algorithm.to_owned(),
+            encryption: None,
+            key_id: header.key_id.clone(),
+            content_type: content_type.map(str::to_owned),
+            status,
+        }
+    }
+
+    fn encrypted(header: &JweHeader, status: LayerStatus) -> Self {
+        Self {
+            is_encrypted: true,
+            algorithm: header.algorithm.clone(),
+            encryption: Some(header.encryption.clone()),
+            key_id: header.key_id.clone(),
+            content_type: header.content_type.clone(),
+            status,
+        }
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <li class="list-group-item d-flex flex-wrap align-items-baseline">
+                <strong class="me-3">
+                    { if self.is_encrypted { "Encrypted token" } else { "Signed token" } }
+                </strong>
+                { view_header_field("alg", Some(&self.algorithm)) }
+                { view_header_field("enc", self.encryption.as_deref()) }
+                { view_header_field("kid", self.key_id.as_deref()) }
+                { view_header_field("cty", self.content_type.as_deref()) }
+                { self.status.view() }
+            </li>
+        }
+    }
+}
+
+/// Verification result for a single signature of a token in the JWS JSON serialization.
+#[derive(Debug)]
+struct SignatureCheck {
+    algorithm: String,
+    key_id: Option<String>,
+    status: LayerStatus,
+    /// Verification error if `status` is [`LayerStatus::Failed`], or an explanation why
+    /// the signature was not verified.
+    error: Option<String>,
+}
+
+impl SignatureCheck {
+    fn view(&self, index: usize) -> Html {
+        html! {
+            <li class="list-group-item">
+                <div class="d-flex flex-wrap align-items-baseline">
+                    <strong class="me-3">{ format!("Signature #{}", index + 1) }</strong>
+                    { view_header_field("alg", Some(&self.algorithm)) }
+                    { view_header_field("kid", self.key_id.as_deref()) }
+                    { self.status.view() }
+                </div>
+                { if let Some(err) = &self.error {
+                    let class = if matches!(self.status, LayerStatus::Failed) {
+                        "small text-danger"
+                    } else {
+                        "small text-muted"
+                    };
+                    html! { <div class={class}>{ err }</div> }
+                } else {
+                    html! {}
+                }}
+            </li>
+        }
+    }
+}
+
+fn view_header_field(name: &str, value: Option<&str>) -> Html {
+    if let Some(value) = value {
+        html! {
+            <span class="me-3 text-nowrap">
+                <span class="text-muted">{ name }{ ": " }</span><code>{ value }</code>
+            </span>
+        }
+    } else {
+        html! {}
+    }
+}
+
+/// Mismatch between a certificate thumbprint in the token header and the certificate
+/// of the verifying key.
+#[derive(Debug)]
+struct ThumbprintMismatch {
+    /// Header field: `x5t` or `x5t#S256`.
+    field: &'static str,
+    /// Thumbprint from the token header.
+    actual: String,
+    /// Thumbprint of the verifying key certificate.
+    expected: String,
+}
+
+impl ThumbprintMismatch {
+    fn view(&self) -> Html {
+        html! {
+            <li>
+                <code>{ self.field }</code>{ ": " }<code>{ &self.actual }</code>
+                { ", while the key certificate has " }<code>{ &self.expected }</code>
+            </li>
+        }
+    }
+}
+
+/// Structure of the processed token: layers of a nested token, and signatures of a token
+/// in the JWS JSON serialization.
+#[derive(Debug, Default)]
+struct TokenStructure {
+    /// Layers of the token; empty if there is no token.
+    layers: Vec<TokenLayer>,
+    /// Signatures of a token in the JWS JSON serialization; empty for other tokens.
+    signatures: Vec<SignatureCheck>,
+    /// Certificate thumbprint mismatches for successfully verified signatures.
+    thumbprint_mismatches: Vec<ThumbprintMismatch>,
+}
+
+/// Processing status of a [`TokenLayer`] or a [`SignatureCheck`].
+#[derive(Debug)]
+enum LayerStatus {
+    /// Layer signature was verified. The key is only set if it is not obvious which key was used.
+    Verified { key: Option<String> },
+    /// Layer was decrypted. The key is only set if it is not obvious which key was used.
+    Decrypted { key: Option<String> },
+    /// Layer was not verified because no key is provided.
+    NotVerified,
+    /// Layer was not decrypted because no key is provided.
+    NotDecrypted,
+    /// Layer has failed verification or decryption.
+    Failed,
+}
+
+impl LayerStatus {
+    fn view(&self) -> Html {
+        let (class, text, key) = match self {
+            Self::Verified { key } => ("bg-success", "verified", key.as_deref()),
+            Self::Decrypted { key } => ("bg-success", "decrypted", key.as_deref()),
+            Self::NotVerified => ("bg-warning text-dark", "not verified", None),
+            Self::NotDecrypted => ("bg-warning text-dark", "not decrypted", None),
+            Self::Failed => ("bg-danger", "invalid", None),
+        };
+        html! {
+            <span class="ms-auto">
+                <span class={classes!("badge", class)}>{ text }</span>
+                { if let Some(key) = key {
+                    html! { <small class="text-muted">{ " with " }{ key }</small> }
+                } else {
+                    html! {}
+                }}
+            </span>
+        }
+    }
+}
+
+/// Settings for validating time-based claims (`exp` and `nbf`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeSettings {
+    /// Instant at which claims are evaluated. If not set, the current time is used.
+    evaluated_at: Option<DateTime<Utc>>,
+    /// Leeway accounting for clock skew between the token issuer and the verifier.
+    leeway: Duration,
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            evaluated_at: None,
+            leeway: TimeOptions::default().leeway,
+        }
+    }
+}
+
+impl TimeSettings {
+    /// Format of values for `datetime-local` inputs.
+    const INPUT_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S";
+
+    fn time_options(self) -> TimeOptions<impl Fn() -> DateTime<Utc>> {
+        let evaluated_at = self.evaluated_at;
+        TimeOptions::new(self.leeway, move || evaluated_at.unwrap_or_else(Utc::now))
+    }
+
+    fn view_summary(self) -> Html {
+        html! {
+            <p id="time-settings-info" class="text-muted">
+                { Icon::Info.view() }
+                { " Time-based claims were validated " }
+                { if let Some(evaluated_at) = self.evaluated_at {
+                    html! { <>{ "at " }<strong>{ evaluated_at.to_string() }</strong></> }
+                } else {
+                    html! { <strong>{ "at the current time" }</strong> }
+                }}
+                { " with " }
+                <strong>{ format!("{} s", self.leeway.num_seconds()) }</strong>
+                { " leeway." }
+            </p>
         }
     }
 }
 
 #[derive(Debug)]
 struct AppState {
-    key: Option<KeyInstance>,
-    token: Option<UntrustedToken<'static>>,
+    key: Option<KeySet>,
+    /// Thumbprint of the key last loaded from the `jwk` header of a token.
+    embedded_key_thumbprint: Option<String>,
+    token: Option<InputToken>,
+    time: TimeSettings,
+    expectations: Expectations,
     result: TokenResult,
+    structure: TokenStructure,
+    /// Failed expectations for the processed token.
+    expectation_errors: Vec<ExpectationError>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             key: None,
+            embedded_key_thumbprint: None,
             token: None,
+            time: TimeSettings::default(),
+            expectations: Expectations::default(),
             result: TokenResult::None,
+            structure: TokenStructure::default(),
+            expectation_errors: vec![],
         }
     }
 }
 
 impl AppState {
+    /// Algorithm name for unsecured JWTs as per RFC 7519, section 6.
+    const UNSECURED_ALGORITHM: &'static str = "none";
+    /// Critical header extensions supported for tokens with detached / unencoded payload.
+    const SUPPORTED_DETACHED_EXTENSIONS: &'static [&'static str] = &["b64"];
+
+    /// Checks whether the verifying key set consists of the key embedded into a token.
+    fn is_key_embedded(&self) -> bool {
+        let (Some(key_set), Some(thumbprint)) = (&self.key, &self.embedded_key_thumbprint) else {
+            return false;
+        };
+        key_set
+            .entries()
+            .iter()
+            .all(|entry| entry.thumbprint == *thumbprint)
+    }
+
+    /// Returns claims of the processed token, including ones that have failed verification.
+    fn claims(&self) -> Option<&GenericClaims> {
+        match &self.result {
+            TokenResult::Ok { claims, .. } => Some(claims),
+            TokenResult::Err { claims, .. } => claims.as_ref(),
+            TokenResult::None => None,
+        }
+    }
+
+    fn key_usage(&self) -> KeyUsage {
+        if matches!(self.token, Some(InputToken::Encrypted(_))) {
+            KeyUsage::Decryption
+        } else {
+            KeyUsage::Verification
+        }
+    }
+
     fn update(&mut self) {
-        let Some(token) = &self.token else {
-            self.result = TokenResult::None;
-            return;
+        self.structure = TokenStructure::default();
+        self.result = match &self.token {
+            None => TokenResult::None,
+            Some(token) => Self::process(self.key.as_ref(), token, &mut self.structure),
         };
+        self.expectation_errors = self.check_expectations();
+    }
 
-        let Some(key) = &self.key else {
-            let claims = token
-                .deserialize_claims_unchecked::<serde_json::Value>()
-                .ok();
-            self.result = TokenResult::Err {
-                err: ExtendedValidationError::NoKey,
-                claims,
+    /// Checks the processed token against [`Expectations`]. Signing algorithms are checked
+    /// for all signed layers and signatures.
+    fn check_expectations(&self) -> Vec<ExpectationError> {
+        if self.token.is_none() {
+            return vec![];
+        }
+        let layer_algorithms = self
+            .structure
+            .layers
+            .iter()
+            .filter(|layer| !layer.is_encrypted)
+            .map(|layer| layer.algorithm.as_str());
+        let signature_algorithms = self
+            .structure
+            .signatures
+            .iter()
+            .map(|check| check.algorithm.as_str());
+        let mut errors = self
+            .expectations
+            .check_algorithms(layer_algorithms.chain(signature_algorithms));
+        if let Some(claims) = self.claims() {
+            errors.extend(self.expectations.check_claims(claims));
+        }
+        errors
+    }
+
+    /// Processes a token layer, recursing into nested tokens if necessary.
+    fn process(
+        key_set: Option<&KeySet>,
+        token: &InputToken,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        match token {
+            InputToken::Signed(token) => {
+                let result = Self::verify(key_set, token, structure);
+                let status = Self::signature_status(&result);
+                let layer = TokenLayer::signed(token.algorithm(), token.header(), None, status);
+                structure.layers.push(layer);
+                result
+            }
+            InputToken::Detached(token) => {
+                let result = Self::verify_detached(key_set, token, structure);
+                let status = Self::signature_status(&result);
+                let layer = TokenLayer::signed(token.algorithm(), token.header(), None, status);
+                structure.layers.push(layer);
+                result
+            }
+            InputToken::Nested(token) => Self::process_nested(key_set, token, structure),
+            InputToken::Encrypted(token) => Self::process_encrypted(key_set, token, structure),
+            InputToken::Json(token) => Self::process_json(key_set, token, structure),
+        }
+    }
+
+    fn signature_status(result: &TokenResult) -> LayerStatus {
+        match result {
+            TokenResult::Ok { verifying_key, .. } => LayerStatus::Verified {
+                key: verifying_key.clone(),
+            },
+            TokenResult::Err {
+                err: ExtendedValidationError::NoKey | ExtendedValidationError::NoPayload,
+                ..
+            } => LayerStatus::NotVerified,
+            _ => LayerStatus::Failed,
+        }
+    }
+
+    fn process_nested(
+        key_set: Option<&KeySet>,
+        token: &NestingToken,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        let (status, err) = if token.algorithm() == Self::UNSECURED_ALGORITHM {
+            let err = ExtendedValidationError::Unsecured {
+                has_signature: !token.signature_bytes().is_empty(),
             };
-            return;
+            (LayerStatus::Failed, Some(err))
+        } else if let Err(fields) = Self::check_critical(token.critical_fields(), &[]) {
+            let err = ExtendedValidationError::UnsupportedCritical(fields);
+            (LayerStatus::Failed, Some(err))
+        } else if let Some(key_set) = key_set {
+            match key_set.verify_signature(token.signature_input()) {
+                Ok(entry) => {
+                    Self::check_header_thumbprints(token.header(), entry, structure);
+                    let key = Self::describe_key(key_set, entry);
+                    (LayerStatus::Verified { key }, None)
+                }
+                Err(err) => (LayerStatus::Failed, Some(err.into())),
+            }
+        } else {
+            (
+                LayerStatus::NotVerified,
+                Some(ExtendedValidationError::NoKey),
+            )
         };
+        let content_type = Some(token.content_type());
+        structure.layers.push(TokenLayer::signed(
+            token.algorithm(),
+            token.header(),
+            content_type,
+            status,
+        ));
+
+        // The nested token is processed even if this layer is invalid, so that its claims
+        // can be inspected. The error for the outer layer takes precedence, though.
+        let inner_result = match Self::parse_nested_token(token.payload()) {
+            Ok(inner_token) => Self::process(key_set, &inner_token, structure),
+            Err(inner_err) => TokenResult::Err {
+                err: ExtendedValidationError::MalformedNestedToken(inner_err),
+                claims: None,
+            },
+        };
+        match (err, inner_result) {
+            (None, inner_result) => inner_result,
+            (Some(err), TokenResult::Ok { claims, .. }) => TokenResult::Err {
+                err,
+                claims: Some(*claims),
+            },
+            (Some(err), TokenResult::Err { claims, .. }) => TokenResult::Err { err, claims },
+            (Some(err), TokenResult::None) => TokenResult::Err { err, claims: None },
+        }
+    }
 
-        self.result = match key.verify_token(token) {
-            Ok(token) => TokenResult::Ok(Box::new(token)),
+    fn process_encrypted(
+        key_set: Option<&KeySet>,
+        token: &EncryptedToken,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        let header = token.header();
+        if let Err(fields) = Self::check_critical(&header.critical, &[]) {
+            structure
+                .layers
+                .push(TokenLayer::encrypted(header, LayerStatus::Failed));
+            return TokenResult::Err {
+                err: ExtendedValidationError::UnsupportedCritical(fields),
+                claims: None,
+            };
+        }
+        let Some(key_set) = key_set else {
+            structure
+                .layers
+                .push(TokenLayer::encrypted(header, LayerStatus::NotDecrypted));
+            return TokenResult::Err {
+                err: ExtendedValidationError::NoDecryptionKey,
+                claims: None,
+            };
+        };
+        let (payload, entry) = match key_set.decrypt_token(token) {
+            Ok(output) => output,
             Err(err) => {
-                let claims = if matches!(err, ValidationError::MalformedClaims(_)) {
-                    // No sense to try deserializing claims again.
-                    None
-                } else {
-                    token
-                        .deserialize_claims_unchecked::<serde_json::Value>()
-                        .ok()
+                structure
+                    .layers
+                    .push(TokenLayer::encrypted(header, LayerStatus::Failed));
+                return TokenResult::Err {
+                    err: ExtendedValidationError::Decryption(err),
+                    claims: None,
+                };
+            }
+        };
+        let decryption_key = Self::describe_key(key_set, entry);
+        let status = LayerStatus::Decrypted {
+            key: decryption_key.clone(),
+        };
+        structure.layers.push(TokenLayer::encrypted(header, status));
+
+        // If the payload is a token, it is processed as usual. Otherwise, the payload
+        // is interpreted as claims; their integrity is ensured by authenticated encryption.
+        let is_nested = header
+            .content_type
+            .as_deref()
+            .is_some_and(is_nested_content_type);
+        match Self::parse_nested_token(&payload) {
+            Ok(nested_token) => {
+                let mut result = Self::process(Some(key_set), &nested_token, structure);
+                if let TokenResult::Ok { decryption, .. } = &mut result {
+                    decryption.get_or_insert(DecryptionInfo {
+                        key: decryption_key,
+                        is_signed: true,
+                    });
+                }
+                return result;
+            }
+            Err(err) if is_nested => {
+                return TokenResult::Err {
+                    err: ExtendedValidationError::MalformedNestedToken(err),
+                    claims: None,
+                };
+            }
+            Err(_) => { /* interpret the payload as claims */ }
+        }
+
+        match serde_json::from_slice::<GenericClaims>(&payload) {
+            Ok(claims) => TokenResult::Ok {
+                claims: Box::new(claims),
+                verifying_key: None,
+                decryption: Some(DecryptionInfo {
+                    key: decryption_key,
+                    is_signed: false,
+                }),
+            },
+            Err(err) => TokenResult::Err {
+                err: ValidationError::MalformedClaims(err).into(),
+                claims: None,
+            },
+        }
+    }
+
+    fn process_json(
+        key_set: Option<&KeySet>,
+        token: &JsonToken,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        for signature in token.signatures() {
+            let (status, error) = if signature.algorithm() == Self::UNSECURED_ALGORITHM {
+                let err = "unsecured signatures (`alg: none`) are never accepted".to_owned();
+                (LayerStatus::Failed, Some(err))
+            } else if let Err(fields) = Self::check_critical(signature.critical_fields(), &[]) {
+                let err = format!("unsupported critical header(s): {}", fields.join(", "));
+                (LayerStatus::Failed, Some(err))
+            } else if key_set
+                .is_some_and(|keys| !keys.has_matching_key(signature.signature_input()))
+            {
+                // Not counted as a failure: the signature may be intended for another verifier.
+                let note = "no matching key: neither key ID nor algorithm match any \
+                    of the provided keys";
+                (LayerStatus::NotVerified, Some(note.to_owned()))
+            } else if let Some(key_set) = key_set {
+                match key_set.verify_signature(signature.signature_input()) {
+                    Ok(entry) => {
+                        let header_str = |field: &str| {
+                            let value = signature
+                                .protected_header
+                                .get(field)
+                                .or_else(|| signature.unprotected_header.get(field))?;
+                            Some(value.as_str()?.trim_end_matches('=').to_owned())
+                        };
+                        let (sha1, sha256) = (header_str("x5t"), header_str("x5t#S256"));
+                        Self::check_thumbprints(sha1, sha256, entry, structure);
+                        let key = Self::describe_key(key_set, entry);
+                        (LayerStatus::Verified { key }, None)
+                    }
+                    Err(err) => (LayerStatus::Failed, Some(err.to_string())),
+                }
+            } else {
+                (LayerStatus::NotVerified, None)
+            };
+            structure.signatures.push(SignatureCheck {
+                algorithm: signature.algorithm().to_owned(),
+                key_id: signature.key_id().map(str::to_owned),
+                status,
+                error,
+            });
+        }
+
+        let claims = match serde_json::from_slice::<GenericClaims>(token.payload()) {
+            Ok(claims) => claims,
+            Err(err) => {
+                return TokenResult::Err {
+                    err: ValidationError::MalformedClaims(err).into(),
+                    claims: None,
                 };
-                TokenResult::Err {
-                    err: err.into(),
-                    claims,
+            }
+        };
+        let failed = structure
+            .signatures
+            .iter()
+            .filter(|check| matches!(check.status, LayerStatus::Failed))
+            .count();
+        let err = if failed > 0 {
+            ExtendedValidationError::InvalidSignatures {
+                failed,
+                total: structure.signatures.len(),
+            }
+        } else if !structure
+            .signatures
+            .iter()
+            .any(|check| matches!(check.status, LayerStatus::Verified { .. }))
+        {
+            // Either no keys are provided, or none of them match the signatures.
+            ExtendedValidationError::NoKey
+        } else {
+            return TokenResult::Ok {
+                claims: Box::new(claims),
+                verifying_key: None,
+                decryption: None,
+            };
+        };
+        TokenResult::Err {
+            err,
+            claims: Some(claims),
+        }
+    }
+
+    fn parse_nested_token(payload: &[u8]) -> Result<InputToken, TokenParseError> {
+        let invalid_structure = || TokenParseError::Signed(ParseError::InvalidTokenStructure);
+        let payload = std::str::from_utf8(payload).map_err(|_| invalid_structure())?;
+        match InputToken::new(payload)? {
+            // JWTs always use the compact serialization (RFC 7519, section 1).
+            InputToken::Json(_) => Err(invalid_structure()),
+            token => Ok(token),
+        }
+    }
+
+    fn verify(
+        key_set: Option<&KeySet>,
+        token: &UntrustedToken<'_>,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        let err = if token.algorithm() == Self::UNSECURED_ALGORITHM {
+            ExtendedValidationError::Unsecured {
+                has_signature: !token.signature_bytes().is_empty(),
+            }
+        } else if let Some(key_set) = key_set {
+            match key_set.verify_token(token) {
+                Ok((token, entry)) => {
+                    Self::check_header_thumbprints(token.header(), entry, structure);
+                    return TokenResult::Ok {
+                        claims: Box::new(token.into_parts().1),
+                        verifying_key: Self::describe_key(key_set, entry),
+                        decryption: None,
+                    };
                 }
+                Err(err) => err.into(),
             }
+        } else {
+            ExtendedValidationError::NoKey
         };
+
+        let claims = if matches!(
+            err,
+            ExtendedValidationError::Err(ValidationError::MalformedClaims(_))
+        ) {
+            // No sense to try deserializing claims again.
+            None
+        } else {
+            token
+                .deserialize_claims_unchecked::<serde_json::Value>()
+                .ok()
+        };
+        TokenResult::Err { err, claims }
+    }
+
+    /// Verifies a token with a detached and / or unencoded payload. The payload is interpreted
+    /// as claims, same as for ordinary signed tokens.
+    fn verify_detached(
+        key_set: Option<&KeySet>,
+        token: &DetachedToken,
+        structure: &mut TokenStructure,
+    ) -> TokenResult {
+        let err = if token.algorithm() == Self::UNSECURED_ALGORITHM {
+            ExtendedValidationError::Unsecured {
+                has_signature: !token.signature_bytes().is_empty(),
+            }
+        } else if let Err(fields) =
+            Self::check_critical(token.critical_fields(), Self::SUPPORTED_DETACHED_EXTENSIONS)
+        {
+            ExtendedValidationError::UnsupportedCritical(fields)
+        } else if let (Some(input), Some(payload)) = (token.signature_input(), token.payload()) {
+            if let Some(key_set) = key_set {
+                match key_set.verify_signature(input) {
+                    Ok(entry) => {
+                        Self::check_header_thumbprints(token.header(), entry, structure);
+                        return match serde_json::from_slice::<GenericClaims>(payload) {
+                            Ok(claims) => TokenResult::Ok {
+                                claims: Box::new(claims),
+                                verifying_key: Self::describe_key(key_set, entry),
+                                decryption: None,
+                            },
+                            Err(err) => TokenResult::Err {
+                                err: ValidationError::MalformedClaims(err).into(),
+                                claims: None,
+                            },
+                        };
+                    }
+                    Err(err) => err.into(),
+                }
+            } else {
+                ExtendedValidationError::NoKey
+            }
+        } else {
+            ExtendedValidationError::NoPayload
+        };
+
+        let claims = token
+            .payload()
+            .and_then(|payload| serde_json::from_slice(payload).ok());
+        TokenResult::Err { err, claims }
+    }
+
+    /// Checks that all critical header extensions (`crit`) are supported. Returns unsupported
+    /// extensions on error.
+    fn check_critical(critical_fields: &[String], supported: &[&str]) -> Result<(), Vec<String>> {
+        let unsupported: Vec<_> = critical_fields
+            .iter()
+            .filter(|&field| !supported.contains(&field.as_str()))
+            .cloned()
+            .collect();
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(unsupported)
+        }
+    }
+
+    fn check_header_thumbprints<H>(
+        header: &Header<H>,
+        entry: &KeySetEntry,
+        structure: &mut TokenStructure,
+    ) {
+        let sha1 = header
+            .certificate_sha1_thumbprint
+            .as_ref()
+            .map(Self::encode_thumbprint);
+        let sha256 = header
+            .certificate_thumbprint
+            .as_ref()
+            .map(Self::encode_thumbprint);
+        Self::check_thumbprints(sha1, sha256, entry, structure);
+    }
+
+    /// Returns the thumbprint as it was presented in the token header, with base64url padding
+    /// trimmed in the same way as for JSON tokens. Serialization is delegated to `jwt-compact`,
+    /// so that opaque string thumbprints (e.g., hex-encoded digests) are reproduced verbatim.
+    fn encode_thumbprint<const N: usize>(thumbprint: &Thumbprint<N>) -> String {
+        let value = serde_json::to_value(thumbprint).expect_throw("cannot serialize thumbprint");
+        let value = value.as_str().unwrap_or_default();
+        value.trim_end_matches('=').to_owned()
+    }
+
+    /// Compares certificate thumbprints from the token header (`x5t` and `x5t#S256`) with
+    /// the certificate of the verifying key, if the latter is known.
+    fn check_thumbprints(
+        sha1: Option<String>,
+        sha256: Option<String>,
+        entry: &KeySetEntry,
+        structure: &mut TokenStructure,
+    ) {
+        let Some(certificate) = &entry.certificate else {
+            return;
+        };
+        let checks = [
+            ("x5t", sha1, &certificate.sha1),
+            ("x5t#S256", sha256, &certificate.sha256),
+        ];
+        for (field, actual, expected) in checks {
+            if let Some(actual) = actual {
+                if actual != *expected {
+                    structure.thumbprint_mismatches.push(ThumbprintMismatch {
+                        field,
+                        actual,
+                        expected: expected.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Describes the key from a key set if it's not obvious which key was used.
+    fn describe_key(key_set: &KeySet, entry: &KeySetEntry) -> Option<String> {
+        let is_ambiguous = key_set.entries().len() > 1 || entry.key_id.is_some();
+        is_ambiguous.then(|| entry.to_string())
     }
 }
 
 #[derive(Debug)]
 pub enum AppMessage {
-    SetKey(Option<Box<KeyInstance>>),
-    SetToken(Option<Box<UntrustedToken<'static>>>),
+    SetKey(Option<Box<KeySet>>),
+    SetToken(Option<Box<InputToken>>),
     SetSaveFlag(bool),
     RandomToken,
+    SetRandomTokenAlgorithm(&'static str),
+    SetKeyPair(Box<GeneratedKeyPair>),
+    /// Loads the key embedded into the token header (`jwk`) into the key input.
+    UseEmbeddedKey(String),
+    /// Sets the instant at which time-based claims are evaluated; `None` means the current time.
+    SetEvaluationTime(Option<DateTime<Utc>>),
+    /// Sets the leeway for validating time-based claims.
+    SetLeeway(Duration),
+    /// Re-renders time-based claims, e.g. once the token crosses `exp` or `nbf`.
+    RefreshTime,
+    /// Sets a field of verifier expectations from a raw string.
+    SetExpectation(ExpectationField, String),
 }
 
 impl AppMessage {
     pub fn new_key(key: Option<KeyInstance>) -> Self {
-        Self::SetKey(key.map(Box::new))
+        Self::new_key_set(key.map(KeySet::from))
+    }
+
+    pub fn new_key_set(key_set: Option<KeySet>) -> Self {
+        Self::SetKey(key_set.map(Box::new))
     }
 
     pub fn new_token(token: Option<UntrustedToken<'static>>) -> Self {
+        Self::new_input_token(token.map(InputToken::Signed))
+    }
+
+    pub fn new_input_token(token: Option<InputToken>) -> Self {
         Self::SetToken(token.map(Box::new))
     }
+
+    fn random_alg_set(event: &Event) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for algorithm set event");
+        let target = target
+            .dyn_into::<HtmlSelectElement>()
+            .expect_throw("unexpected target for algorithm set event");
+        let alg = SigningKeyInstance::ALGORITHMS
+            .into_iter()
+            .find(|&alg| alg == target.value())
+            .unwrap_or(App::DEFAULT_RANDOM_TOKEN_ALG);
+        Self::SetRandomTokenAlgorithm(alg)
+    }
+
+    fn input_value(event: &Event) -> String {
+        let target = event.target().expect_throw("no target for input event");
+        let target = target
+            .dyn_into::<HtmlInputElement>()
+            .expect_throw("unexpected target for input event");
+        target.value()
+    }
+
+    fn evaluation_time_set(event: &Event) -> Self {
+        let value = Self::input_value(event);
+        // Depending on the browser, seconds may be omitted from the input value.
+        let evaluated_at = NaiveDateTime::parse_from_str(&value, TimeSettings::INPUT_FORMAT)
+            .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M"))
+            .ok()
+            .map(|time| time.and_utc());
+        Self::SetEvaluationTime(evaluated_at)
+    }
+
+    fn expectation_set(field: ExpectationField, event: &Event) -> Self {
+        Self::SetExpectation(field, Self::input_value(event))
+    }
+
+    fn leeway_set(event: &Event) -> Self {
+        let value = Self::input_value(event);
+        let seconds = value.trim().parse::<u32>().unwrap_or(0);
+        Self::SetLeeway(Duration::try_seconds(seconds.into()).unwrap_throw())
+    }
+
+    pub fn new_key_pair(key_pair: GeneratedKeyPair) -> Self {
+        Self::SetKeyPair(Box::new(key_pair))
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Properties)]
@@ -181,12 +1133,27 @@ pub struct AppProperties {
 pub struct App {
     key_input: ComponentRef<KeyInput>,
     token_input: ComponentRef<TokenInput>,
+    token_signer: ComponentRef<TokenSigner>,
     state: AppState,
     save: bool,
+    random_token_alg: &'static str,
+    /// Private key for the last generated random token.
+    random_private_key: Option<String>,
+    /// Pending re-rendering of time-based claims.
+    time_refresh: Option<Timeout>,
 }
 
 impl App {
-    fn view_claims(claims: &GenericClaims, err: Option<&ExtendedValidationError>) -> Html {
+    const DEFAULT_RANDOM_TOKEN_ALG: &'static str = "HS256";
+
+    fn view_claims(
+        claims: &GenericClaims,
+        time: TimeSettings,
+        verifying_key: Option<&str>,
+        decryption: Option<&DecryptionInfo>,
+        err: Option<&ExtendedValidationError>,
+    ) -> Html {
+        let has_time_claims = claims.expiration.is_some() || claims.not_before.is_some();
         html! {
             <>
                 { if let Some(err) = err {
@@ -194,6 +1161,21 @@ impl App {
                 } else {
                     html! {}
                 }}
+                { if let Some(decryption) = decryption {
+                    Self::view_decryption_info(decryption)
+                } else {
+                    html! {}
+                }}
+                { if let Some(verifying_key) = verifying_key {
+                    Self::view_verifying_key(verifying_key)
+                } else {
+                    html! {}
+                }}
+                { if has_time_claims {
+                    time.view_summary()
+                } else {
+                    html! {}
+                }}
                 { Self::view_claims_nav() }
                 <div class="tab-content">
                     <div
@@ -202,18 +1184,70 @@ impl App {
                         role="tabpanel"
                         aria-labelledby="decoded-claims-tab">
 
-                        { Self::view_decoded_claims(claims) }
-                    </div>
-                    <div
-                        class="tab-pane fade"
-                        id="raw-claims"
-                        role="tabpanel"
-                        aria-labelledby="raw-claims-tab">
+                        { Self::view_decoded_claims(claims, time) }
+                    </div>
+                    <div
+                        class="tab-pane fade"
+                        id="raw-claims"
+                        role="tabpanel"
+                        aria-labelledby="raw-claims-tab">
+
+                        { Self::view_raw_claims(claims) }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    fn view_layers(layers: &[TokenLayer]) -> Html {
+        html! {
+            <div id="token-layers" class="mb-3">
+                <h5>{ "Nested token layers" }</h5>
+                <ol class="list-group list-group-numbered">
+                    { for layers.iter().map(TokenLayer::view) }
+                </ol>
+            </div>
+        }
+    }
+
+    fn view_signatures(signatures: &[SignatureCheck]) -> Html {
+        html! {
+            <div id="token-signatures" class="mb-3">
+                <h5>{ "Signatures" }</h5>
+                <ul class="list-group">
+                    { for signatures.iter().enumerate().map(|(i, check)| check.view(i)) }
+                </ul>
+            </div>
+        }
+    }
+
+    fn view_verifying_key(verifying_key: &str) -> Html {
+        html! {
+            <p id="verifying-key" class="text-muted">
+                { Icon::Info.view() }
+                { " Token was verified with " }
+                <strong>{ verifying_key }</strong>
+                { "." }
+            </p>
+        }
+    }
 
-                        { Self::view_raw_claims(claims) }
-                    </div>
-                </div>
-            </>
+    fn view_decryption_info(decryption: &DecryptionInfo) -> Html {
+        html! {
+            <p id="decryption-info" class="text-muted">
+                { Icon::Info.view() }
+                { if let Some(key) = &decryption.key {
+                    html! { <>{ " Token was decrypted with " }<strong>{ key }</strong>{ "." }</> }
+                } else {
+                    html! { " Token was decrypted." }
+                }}
+                { if decryption.is_signed {
+                    " Its payload is a signed token, which was verified separately."
+                } else {
+                    " Its payload is not signed; claims integrity is ensured \
+                      by authenticated encryption only."
+                }}
+            </p>
         }
     }
 
@@ -247,33 +1281,22 @@ impl App {
         }
     }
 
-    fn view_decoded_claims(claims: &GenericClaims) -> Html {
+    fn view_decoded_claims(claims: &GenericClaims, time: TimeSettings) -> Html {
+        let time_options = time.time_options();
+        let now = (time_options.clock_fn)();
         let mut time_claims_html = Vec::with_capacity(3);
-        if let Some(expiration) = &claims.expiration {
-            let err = claims.validate_expiration(&TimeOptions::default()).err();
-            let html = Self::view_claim(
-                "exp",
-                StandardClaim::by_name("exp"),
-                expiration,
-                false,
-                err.as_ref(),
-            );
+        if let Some(expiration) = claims.expiration {
+            let err = claims.validate_expiration(&time_options).err();
+            let html = Self::view_time_claim("exp", expiration, now, err.as_ref());
             time_claims_html.push(("exp", html));
         }
-        if let Some(issued_at) = &claims.issued_at {
-            let html =
-                Self::view_claim("iat", StandardClaim::by_name("iat"), issued_at, false, None);
+        if let Some(issued_at) = claims.issued_at {
+            let html = Self::view_time_claim("iat", issued_at, now, None);
             time_claims_html.push(("iat", html));
         }
-        if let Some(not_before) = &claims.not_before {
-            let err = claims.validate_maturity(&TimeOptions::default()).err();
-            let html = Self::view_claim(
-                "nbf",
-                StandardClaim::by_name("nbf"),
-                not_before,
-                false,
-                err.as_ref(),
-            );
+        if let Some(not_before) = claims.not_before {
+            let err = claims.validate_maturity(&time_options).err();
+            let html = Self::view_time_claim("nbf", not_before, now, err.as_ref());
             time_claims_html.push(("nbf", html));
         }
 
@@ -309,8 +1332,8 @@ impl App {
     }
 
     fn view_claim_category(category_id: &str, claims_html: Html) -> Html {
-        let title =
-            ClaimCategory::get(category_id).map_or("Other claims", |category| category.title);
+        let category = ClaimCategory::get(category_id);
+        let title = category.map_or("Other claims", |category| category.title);
         let header_id = format!("claim-cat-{category_id}-head");
         let body_id = format!("claim-cat-{category_id}");
         html! {
@@ -330,20 +1353,23 @@ impl App {
                     id={body_id}
                     class="accordion-collapse collapse show py-3"
                     aria-labelledby={header_id}>
+                    { if let Some(category) = category {
+                        html! {
+                            <p class="text-muted small mb-2 toggled-description claim-category-description">
+                                { str_to_html(category.description) }
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
                     { claims_html }
                 </div>
             </div>
         }
     }
 
-    fn view_claim(
-        field_name: &str,
-        claim: StandardClaim,
-        value: &dyn fmt::Display,
-        show_as_code: bool,
-        err: Option<&ValidationError>,
-    ) -> Html {
-        let err = err.map_or_else(Html::default, |err| {
+    fn view_validation_err(err: Option<&dyn fmt::Display>) -> Html {
+        err.map_or_else(Html::default, |err| {
             html! {
                 <span class="ms-2 badge bg-warning text-dark">
                     { Icon::Warning.view() }
@@ -351,13 +1377,88 @@ impl App {
                     { err }
                 </span>
             }
-        });
+        })
+    }
+
+    fn view_claim(
+        field_name: &str,
+        claim: StandardClaim,
+        value: &dyn fmt::Display,
+        show_as_code: bool,
+        err: Option<&dyn fmt::Display>,
+    ) -> Html {
+        let err = Self::view_validation_err(err);
         let value = if show_as_code {
             html! { <><code>{ value.to_string() }</code>{ err }</> }
         } else {
             html! { <>{ value.to_string() }{ err }</> }
         };
-        claim.field.with_html_value(value).view_as_claim(field_name)
+        claim.with_html_value(value).view_as_claim(field_name)
+    }
+
+    /// Renders a timestamp claim (`exp`, `iat` or `nbf`) in UTC and in the local time zone,
+    /// together with its raw value and a description relative to `now`.
+    fn view_time_claim(
+        field_name: &str,
+        timestamp: DateTime<Utc>,
+        now: DateTime<Utc>,
+        err: Option<&ValidationError>,
+    ) -> Html {
+        let local_time = timestamp.with_timezone(&Local);
+        let value = html! {
+            <>
+                <span class="time-claim-utc">{ timestamp.to_string() }</span>
+                { Self::view_validation_err(err.map(|err| err as &dyn fmt::Display)) }
+                <div class="small text-muted">
+                    <span class="me-3" title="Local time">
+                        { local_time.format("%Y-%m-%d %H:%M:%S %:z").to_string() }
+                    </span>
+                    <span class="me-3" title="Unix timestamp">
+                        <code>{ timestamp.timestamp() }</code>
+                    </span>
+                    <span class="time-claim-relative">
+                        { Self::describe_relative_time(field_name, timestamp - now) }
+                    </span>
+                </div>
+            </>
+        };
+        StandardClaim::by_name(field_name)
+            .with_html_value(value)
+            .view_as_claim(field_name)
+    }
+
+    /// Describes a timestamp claim relative to the evaluation instant, e.g. "expires in 12 minutes".
+    fn describe_relative_time(field_name: &str, offset: Duration) -> String {
+        let (future_prefix, past_verb) = match field_name {
+            "exp" => ("expires in", "expired"),
+            "nbf" => ("becomes valid in", "became valid"),
+            _ => ("issued in", "issued"),
+        };
+        let duration = Self::describe_duration(offset);
+        if offset > Duration::zero() {
+            format!("{future_prefix} {duration}")
+        } else {
+            format!("{past_verb} {duration} ago")
+        }
+    }
+
+    fn describe_duration(duration: Duration) -> String {
+        const UNITS: [(u64, &str); 5] = [
+            (365 * 86_400, "year"),
+            (86_400, "day"),
+            (3_600, "hour"),
+            (60, "minute"),
+            (1, "second"),
+        ];
+
+        let seconds = duration.num_seconds().unsigned_abs();
+        let (unit_seconds, unit) = UNITS
+            .into_iter()
+            .find(|&(unit_seconds, _)| seconds >= unit_seconds)
+            .unwrap_or((1, "second"));
+        let value = seconds / unit_seconds;
+        let plural = if value == 1 { "" } else { "s" };
+        format!("{value} {unit}{plural}")
     }
 
     fn view_unknown_claim(field_name: &str, value: &str) -> Html {
@@ -369,31 +1470,35 @@ impl App {
 
     fn view_raw_claims(claims: &GenericClaims) -> Html {
         let serialized = serde_json::to_string_pretty(claims).unwrap();
-        html! {
-            <div class="code-snippet">
-                <div class="code-snippet-panel">
-                    <button
-                        type="button"
-                        title="Copy raw claims to clipboard"
-                        data-clipboard-target="#raw-claims-content"
-                        class="btn btn-sm btn-outline-primary btn-copy">
-                        { "Copy" }
-                    </button>
-                </div>
-                <pre><code id="raw-claims-content">{ &serialized }</code></pre>
-            </div>
-        }
+        view_code_snippet(
+            "raw-claims-content",
+            "Copy raw claims to clipboard",
+            &serialized,
+        )
     }
 
     fn view_custom_claim(field_name: &str, value: &serde_json::Value) -> Html {
         let value_str = serde_json::to_string(value).unwrap();
         StandardClaim::get(field_name).map_or_else(
             || Self::view_unknown_claim(field_name, &value_str),
-            |claim| Self::view_claim(field_name, claim, &value_str, true, None),
+            |claim| {
+                let err = claim.format_rule.and_then(|rule| rule.check(value).err());
+                let err = err.as_ref().map(|err| err as &dyn fmt::Display);
+                Self::view_claim(field_name, claim, &value_str, true, err)
+            },
         )
     }
 
-    fn view_no_inputs_hint(link: &Scope<Self>) -> Html {
+    fn view_no_inputs_hint(&self, link: &Scope<Self>) -> Html {
+        let options: Html = SigningKeyInstance::ALGORITHMS
+            .into_iter()
+            .map(|alg| {
+                html! {
+                    <option value={alg} selected={alg == self.random_token_alg}>{ alg }</option>
+                }
+            })
+            .collect();
+
         Alert::Info.view(
             "No key / token",
             html! {
@@ -402,26 +1507,433 @@ impl App {
                         { "Provide valid key and token in the inputs above to \
                            start verification." }
                     </p>
-                    <button
-                        type="button"
-                        class="btn btn-info"
-                        title="This will also generate a symmetric verifying key"
-                        onclick={link.callback(|_| AppMessage::RandomToken)} >
-                        { "Generate random token" }
-                    </button>
+                    <div class="input-group w-auto d-inline-flex">
+                        <button
+                            type="button"
+                            class="btn btn-info"
+                            title="This will also generate a verifying key of the matching type"
+                            onclick={link.callback(|_| AppMessage::RandomToken)} >
+                            { "Generate random token" }
+                        </button>
+                        <select
+                            id="random-token-alg"
+                            class="form-select"
+                            title="Token algorithm"
+                            aria-label="Token algorithm"
+                            onchange={link.callback(|evt| AppMessage::random_alg_set(&evt))}>
+                            { options }
+                        </select>
+                    </div>
+                </>
+            },
+        )
+    }
+
+    fn view_random_private_key(private_jwk: &str) -> Html {
+        html! {
+            <details id="random-private-key" class="mb-4">
+                <summary>{ "Private key for the generated token" }</summary>
+                <p class="text-muted small mt-2">
+                    { "Can be used to sign more tokens in the " }
+                    <em>{ "Create token" }</em>
+                    { " mode. Only the public part of the key is loaded into the verifying \
+                       key input." }
+                </p>
+                { view_code_snippet(
+                    "random-private-key-content",
+                    "Copy private key to clipboard",
+                    private_jwk,
+                ) }
+            </details>
+        }
+    }
+
+    fn view_mode_nav() -> Html {
+        html! {
+            <nav class="nav nav-pills mb-3">
+                <button
+                    class="nav-link active"
+                    id="verify-mode-tab"
+                    data-bs-toggle="tab"
+                    data-bs-target="#verify-mode"
+                    type="button"
+                    role="tab"
+                    aria-controls="verify-mode"
+                    aria-selected="true">{ "Verify token" }</button>
+                <button
+                    class="nav-link"
+                    id="create-mode-tab"
+                    data-bs-toggle="tab"
+                    data-bs-target="#create-mode"
+                    type="button"
+                    role="tab"
+                    aria-controls="create-mode"
+                    aria-selected="false">{ "Create token" }</button>
+                <button
+                    class="nav-link"
+                    id="generate-mode-tab"
+                    data-bs-toggle="tab"
+                    data-bs-target="#generate-mode"
+                    type="button"
+                    role="tab"
+                    aria-controls="generate-mode"
+                    aria-selected="false">{ "Generate keys" }</button>
+            </nav>
+        }
+    }
+
+    fn view_verification(&self, link: &Scope<Self>) -> Html {
+        html! {
+            <>
+                <form class="mb-4">
+                    <div class="mb-3">
+                        <KeyInput
+                            component_ref={self.key_input.clone()}
+                            save={self.save}
+                            usage={self.state.key_usage()}
+                            onchange={link.callback(AppMessage::new_key_set)} />
+                    </div>
+                    <TokenInput
+                        component_ref={self.token_input.clone()}
+                        save={self.save}
+                        evaluated_at={self.state.time.evaluated_at}
+                        onchange={link.callback(AppMessage::new_input_token)}
+                        onembeddedkey={link.callback(AppMessage::UseEmbeddedKey)} />
+                    { self.view_time_settings(link) }
+                    { self.view_expectations(link) }
+                </form>
+
+                { if let Some(private_jwk) = &self.random_private_key {
+                    Self::view_random_private_key(private_jwk)
+                } else {
+                    html! {}
+                }}
+
+                { if self.state.structure.layers.len() > 1 {
+                    Self::view_layers(&self.state.structure.layers)
+                } else {
+                    html! {}
+                }}
+                { if self.state.structure.signatures.is_empty() {
+                    html! {}
+                } else {
+                    Self::view_signatures(&self.state.structure.signatures)
+                }}
+                { if self.state.structure.thumbprint_mismatches.is_empty() {
+                    html! {}
+                } else {
+                    Self::view_thumbprint_warning(&self.state.structure.thumbprint_mismatches)
+                }}
+                { if self.state.expectation_errors.is_empty() {
+                    html! {}
+                } else {
+                    Self::view_expectation_errors(&self.state.expectation_errors)
+                }}
+                { match &self.state.result {
+                    TokenResult::Ok { claims, verifying_key, decryption } => html! {
+                        <>
+                            { if self.state.is_key_embedded() {
+                                Self::view_embedded_key_warning()
+                            } else {
+                                html! {}
+                            }}
+                            { Self::view_claims(
+                                claims,
+                                self.state.time,
+                                verifying_key.as_deref(),
+                                decryption.as_ref(),
+                                None,
+                            ) }
+                        </>
+                    },
+                    TokenResult::Err { err, claims: Some(claims) } =>
+                        Self::view_claims(claims, self.state.time, None, None, Some(err)),
+                    TokenResult::Err { err, claims: None } => err.view(),
+                    TokenResult::None => self.view_no_inputs_hint(link),
+                }}
+            </>
+        }
+    }
+
+    fn view_time_settings(&self, link: &Scope<Self>) -> Html {
+        let time = self.state.time;
+        let evaluated_at = time
+            .evaluated_at
+            .map(|time| time.format(TimeSettings::INPUT_FORMAT).to_string())
+            .unwrap_or_default();
+        view_wide_data_row(
+            html! {
+                <label for="evaluation-time"><strong>{ "Validation time" }</strong></label>
+            },
+            html! {
+                <>
+                    <div class="d-flex flex-wrap gap-2 mb-1">
+                        <div class="input-group input-group-sm w-auto">
+                            <input
+                                id="evaluation-time"
+                                type="datetime-local"
+                                step="1"
+                                class="form-control"
+                                title="Instant at which time-based claims are evaluated"
+                                value={evaluated_at}
+                                onchange={link.callback(|evt| {
+                                    AppMessage::evaluation_time_set(&evt)
+                                })} />
+                            <span class="input-group-text">{ "UTC" }</span>
+                            <button
+                                id="evaluation-time-reset"
+                                type="button"
+                                class="btn btn-outline-secondary"
+                                title="Evaluate time-based claims at the current time"
+                                disabled={time.evaluated_at.is_none()}
+                                onclick={link.callback(|_| AppMessage::SetEvaluationTime(None))}>
+                                { "Now" }
+                            </button>
+                        </div>
+                        <div class="input-group input-group-sm w-auto">
+                            <span class="input-group-text">{ "Leeway" }</span>
+                            <input
+                                id="time-leeway"
+                                type="number"
+                                min="0"
+                                class="form-control"
+                                title="Allowed clock skew for time-based claims"
+                                value={time.leeway.num_seconds().to_string()}
+                                onchange={link.callback(|evt| AppMessage::leeway_set(&evt))} />
+                            <span class="input-group-text">{ "s" }</span>
+                        </div>
+                    </div>
+                    <div class="text-muted small">
+                        { "Expiration (" }<code>{ "exp" }</code>{ ") and maturity (" }
+                        <code>{ "nbf" }</code>
+                        { ") claims are validated at this instant; leave the time empty \
+                           to use the current time." }
+                    </div>
+                </>
+            },
+        )
+    }
+
+    fn view_expectations(&self, link: &Scope<Self>) -> Html {
+        const FIELDS: [(ExpectationField, &str, &str, &str); 5] = [
+            (
+                ExpectationField::Issuers,
+                "expected-issuers",
+                "Issuer(s)",
+                "https://idp.example.com/",
+            ),
+            (
+                ExpectationField::Audience,
+                "expected-audience",
+                "Audience",
+                "https://api.example.com",
+            ),
+            (ExpectationField::Subject, "expected-subject", "Subject", ""),
+            (
+                ExpectationField::RequiredClaims,
+                "required-claims",
+                "Required claims",
+                "exp, iat",
+            ),
+            (
+                ExpectationField::Algorithms,
+                "allowed-algorithms",
+                "Algorithms",
+                "RS256, ES256",
+            ),
+        ];
+
+        let inputs = FIELDS.into_iter().map(|(field, id, title, placeholder)| {
+            html! {
+                <div class="input-group input-group-sm">
+                    <label class="input-group-text w-25" for={id}>{ title }</label>
+                    <input
+                        id={id}
+                        type="text"
+                        class="form-control font-monospace"
+                        placeholder={placeholder}
+                        autocomplete="off"
+                        spellcheck="false"
+                        value={self.state.expectations.get(field)}
+                        onchange={link.callback(move |evt| {
+                            AppMessage::expectation_set(field, &evt)
+                        })} />
+                </div>
+            }
+        });
+        view_wide_data_row(
+            html! {
+                <label for="expected-issuers"><strong>{ "Expectations" }</strong></label>
+            },
+            html! {
+                <>
+                    <div class="d-flex flex-column gap-1 mb-1">{ for inputs }</div>
+                    <div class="text-muted small">
+                        { "Optional checks performed by the token recipient; empty fields \
+                           impose no restrictions. Lists are separated by commas." }
+                    </div>
+                </>
+            },
+        )
+    }
+
+    fn view_expectation_errors(errors: &[ExpectationError]) -> Html {
+        Alert::Danger.view(
+            "Token does not meet expectations",
+            html! {
+                <ul id="expectation-errors" class="card-text">
+                    { for errors.iter().map(|err| html! { <li>{ err.to_string() }</li> }) }
+                </ul>
+            },
+        )
+    }
+
+    fn view_thumbprint_warning(mismatches: &[ThumbprintMismatch]) -> Html {
+        Alert::Warning.view(
+            "Certificate thumbprint mismatch",
+            html! {
+                <>
+                    <p class="card-text">
+                        { "The token was verified, but the certificate thumbprint in its header \
+                           does not match the certificate of the verifying key:" }
+                    </p>
+                    <ul class="card-text">
+                        { for mismatches.iter().map(ThumbprintMismatch::view) }
+                    </ul>
+                    <p class="card-text text-muted">
+                        <small>
+                            { "This usually means that the token issuer is configured with \
+                               a wrong certificate, e.g., a stale one after the certificate \
+                               was renewed." }
+                        </small>
+                    </p>
+                </>
+            },
+        )
+    }
+
+    fn view_embedded_key_warning() -> Html {
+        Alert::Warning.view(
+            "Token is verified with embedded key",
+            html! {
+                <>
+                    <p class="card-text">
+                        { "The verifying key is taken from the header of the token itself \
+                           (the " }<code>{ "jwk" }</code>{ " header or the leaf certificate from \
+                           the " }<code>{ "x5c" }</code>
+                        { " header). Anyone can sign a token with their own key and embed it, \
+                           so the verification only proves that the token was not modified \
+                           after signing. It proves nothing about who has issued the token \
+                           unless the certificate chain is validated against a trusted root." }
+                    </p>
+                    <p class="card-text text-muted">
+                        <small>
+                            { "Self-signed tokens such as " }
+                            <a href="https://www.rfc-editor.org/rfc/rfc9449.html">{ "DPoP proofs" }</a>
+                            { " are only meaningful if the key is bound to an expected party \
+                               by other means, e.g., by comparing its thumbprint to a value \
+                               from an access token." }
+                        </small>
+                    </p>
                 </>
             },
         )
     }
 
-    fn generate_random_token(&self) {
-        let key = KeyInstance::random_key();
-        let token = KeyInstance::random_token(&key);
+    /// Schedules re-rendering of time-based claims if they are evaluated at the current time:
+    /// once the token crosses `exp` or `nbf` (taking leeway into account), and periodically
+    /// so that relative descriptions stay up to date.
+    fn schedule_time_refresh(&mut self, link: &Scope<Self>) {
+        self.time_refresh = None;
+        let time = self.state.time;
+        if time.evaluated_at.is_some() {
+            return;
+        }
+        let Some(claims) = self.state.claims() else {
+            return;
+        };
+
+        let leeway = time.leeway;
+        let expiration = claims
+            .expiration
+            .into_iter()
+            .flat_map(|exp| [Some(exp), exp.checked_add_signed(leeway)]);
+        let not_before = claims
+            .not_before
+            .into_iter()
+            .flat_map(|nbf| [Some(nbf), nbf.checked_sub_signed(leeway)]);
+        let instants: Vec<_> = expiration
+            .chain(not_before)
+            .flatten()
+            .chain(claims.issued_at)
+            .collect();
+
+        let now = Utc::now();
+        let Some(closest) = instants.iter().map(|&instant| (instant - now).abs()).min() else {
+            return;
+        };
+        let mut delay = if closest < Duration::try_minutes(1).unwrap_throw() {
+            Duration::try_seconds(1).unwrap_throw()
+        } else {
+            Duration::try_minutes(1).unwrap_throw()
+        };
+        let next_crossing = instants
+            .iter()
+            .filter(|&&instant| instant > now)
+            .map(|&instant| instant - now)
+            .min();
+        if let Some(next_crossing) = next_crossing {
+            // Validation is strict, so the crossing happens right after the instant.
+            delay = delay.min(next_crossing + Duration::try_milliseconds(1).unwrap_throw());
+        }
+
+        let millis = u32::try_from(delay.num_milliseconds()).unwrap_or(u32::MAX);
+        let link = link.clone();
+        self.time_refresh = Some(Timeout::new(millis, move || {
+            link.send_message(AppMessage::RefreshTime);
+        }));
+    }
+
+    fn use_embedded_key(&mut self, jwk: String) {
+        let thumbprint = serde_json::from_str::<JsonWebKey<'_>>(&jwk)
+            .ok()
+            .and_then(|jwk| KeyInstance::new(&jwk).ok())
+            .map(|key| key.thumbprint());
+        self.state.embedded_key_thumbprint = thumbprint;
+        self.key_input
+            .send_message(KeyInputMessage::SetFormat(KeyInputFormat::Jwk));
+        self.key_input.send_message(KeyInputMessage::SetKey(jwk));
+    }
+
+    fn load_key_pair(&self, key_pair: GeneratedKeyPair) {
+        self.key_input
+            .send_message(KeyInputMessage::SetFormat(KeyInputFormat::Jwk));
+        self.key_input
+            .send_message(KeyInputMessage::SetKey(key_pair.public_jwk));
+        self.token_signer
+            .send_message(TokenSignerMessage::SetKey(key_pair.private_jwk));
+    }
+
+    fn generate_random_token(&mut self) {
+        let alg = self.random_token_alg;
+        let signing_key = SigningKeyInstance::random(alg);
+        let token = signing_key.random_token(alg);
+        let verifying_key = signing_key.to_verifying_key();
         let jwk =
-            serde_json::to_string(&JsonWebKey::from(&key)).expect_throw("cannot serialize key");
+            serde_json::to_string(&verifying_key.to_jwk()).expect_throw("cannot serialize key");
+        self.key_input
+            .send_message(KeyInputMessage::SetFormat(KeyInputFormat::Jwk));
         self.key_input.send_message(KeyInputMessage::SetKey(jwk));
         self.token_input
             .send_message(TokenInputMessage::SetToken(token));
+
+        self.random_private_key = if let SigningKeyInstance::Symmetric(_) = signing_key {
+            None // the private key is the same as the public one
+        } else {
+            let jwk = serde_json::to_string_pretty(&signing_key.to_jwk())
+                .expect_throw("cannot serialize key");
+            Some(jwk)
+        };
     }
 }
 
@@ -433,12 +1945,16 @@ impl Component for App {
         Self {
             key_input: ComponentRef::default(),
             token_input: ComponentRef::default(),
+            token_signer: ComponentRef::default(),
             state: AppState::default(),
             save: ctx.props().save,
+            random_token_alg: Self::DEFAULT_RANDOM_TOKEN_ALG,
+            random_private_key: None,
+            time_refresh: None,
         }
     }
 
-    fn update(&mut self, _: &Context<Self>, message: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, message: Self::Message) -> bool {
         match message {
             AppMessage::SetKey(key) => {
                 self.state.key = key.map(|boxed| *boxed);
@@ -451,37 +1967,64 @@ impl Component for App {
             AppMessage::RandomToken => {
                 self.generate_random_token();
             }
+            AppMessage::SetRandomTokenAlgorithm(alg) => {
+                self.random_token_alg = alg;
+            }
+            AppMessage::SetKeyPair(key_pair) => {
+                self.load_key_pair(*key_pair);
+            }
+            AppMessage::UseEmbeddedKey(jwk) => {
+                self.use_embedded_key(jwk);
+            }
+            AppMessage::SetEvaluationTime(evaluated_at) => {
+                self.state.time.evaluated_at = evaluated_at;
+            }
+            AppMessage::SetLeeway(leeway) => {
+                self.state.time.leeway = leeway;
+            }
+            AppMessage::RefreshTime => { /* the view is re-rendered below */ }
+            AppMessage::SetExpectation(field, value) => {
+                self.state.expectations.set(field, &value);
+                self.state.update();
+            }
             AppMessage::SetSaveFlag(save) => {
                 self.save = save;
             }
         }
+        self.schedule_time_refresh(ctx.link());
         true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let link = ctx.link();
         html! {
             <>
-                <form class="mb-4">
-                    <div class="mb-3">
-                        <KeyInput
-                            component_ref={self.key_input.clone()}
-                            save={self.save}
-                            onchange={link.callback(AppMessage::new_key)} />
+                { Self::view_mode_nav() }
+                <div class="tab-content">
+                    <div
+                        class="tab-pane fade show active"
+                        id="verify-mode"
+                        role="tabpanel"
+                        aria-labelledby="verify-mode-tab">
+
+                        { self.view_verification(ctx.link()) }
                     </div>
-                    <TokenInput
-                        component_ref={self.token_input.clone()}
-                        save={self.save}
-                        onchange={link.callback(AppMessage::new_token)} />
-                </form>
+                    <div
+                        class="tab-pane fade"
+                        id="create-mode"
+                        role="tabpanel"
+                        aria-labelledby="create-mode-tab">
 
-                { match &self.state.result {
-                    TokenResult::Ok(token) => Self::view_claims(token.claims(), None),
-                    TokenResult::Err { err, claims: Some(claims) } =>
-                        Self::view_claims(claims, Some(err)),
-                    TokenResult::Err { err, claims: None } => err.view(),
-                    TokenResult::None => Self::view_no_inputs_hint(link),
-                }}
+                        <TokenSigner component_ref={self.token_signer.clone()} />
+                    </div>
+                    <div
+                        class="tab-pane fade"
+                        id="generate-mode"
+                        role="tabpanel"
+                        aria-labelledby="generate-mode-tab">
+
+                        <KeyGenerator onchange={ctx.link().callback(AppMessage::new_key_pair)} />
+                    </div>
+                </div>
             </>
         }
     }
@@ -0,0 +1,258 @@
+//! Dictionary of standard claims with search and filtering by category.
+
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlInputElement, HtmlSelectElement, InputEvent};
+use yew::{html, Component, Context, Html};
+
+use super::common::{str_to_html, Icon};
+use crate::fields::{ClaimCategory, StandardClaim};
+
+/// Removes HTML tags from a description so that they do not match search queries.
+fn strip_tags(html_str: &str) -> String {
+    let mut text = String::with_capacity(html_str.len());
+    let mut in_tag = false;
+    for ch in html_str.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(ch),
+            _ => { /* skip tag contents */ }
+        }
+    }
+    text
+}
+
+#[derive(Debug)]
+pub enum ClaimsDictionaryMessage {
+    /// Sets the search query.
+    SetQuery(String),
+    /// Sets the displayed category; `None` displays all categories.
+    SetCategory(Option<&'static str>),
+}
+
+impl ClaimsDictionaryMessage {
+    fn query_set(event: &InputEvent) -> Self {
+        let target = event.target().expect_throw("no target for query set event");
+        let target = target
+            .dyn_into::<HtmlInputElement>()
+            .expect_throw("unexpected target for query set event");
+        Self::SetQuery(target.value())
+    }
+
+    fn category_set(event: &Event) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for category set event");
+        let target = target
+            .dyn_into::<HtmlSelectElement>()
+            .expect_throw("unexpected target for category set event");
+        let value = target.value();
+        let category = ClaimCategory::all()
+            .iter()
+            .find_map(|&(id, _)| (id == value).then_some(id));
+        Self::SetCategory(category)
+    }
+}
+
+/// Dictionary of standard claims from `fields.toml`, grouped by category.
+#[derive(Debug, Default)]
+pub struct ClaimsDictionary {
+    /// Lowercased search query.
+    query: String,
+    category: Option<&'static str>,
+}
+
+impl ClaimsDictionary {
+    fn matches(&self, original_name: &str, claim: &StandardClaim) -> bool {
+        if self.query.is_empty() {
+            return true;
+        }
+        let query = self.query.as_str();
+        original_name.to_lowercase().contains(query)
+            || claim.field.name.to_lowercase().contains(query)
+            || strip_tags(claim.field.description)
+                .to_lowercase()
+                .contains(query)
+    }
+
+    fn view_anchor(target: &str, title: &'static str) -> Html {
+        html! {
+            <>
+                { "\u{00a0}" } // non-breakable space
+                <a href={format!("#{target}")}
+                    class="link-secondary text-decoration-none claim-anchor"
+                    title={title}>
+                    { "¶" }
+                </a>
+            </>
+        }
+    }
+
+    fn view_filters(ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let options: Html = ClaimCategory::all()
+            .iter()
+            .map(|(id, category)| {
+                html! {
+                    <option value={*id}>{ category.title }</option>
+                }
+            })
+            .collect();
+
+        html! {
+            <form class="row g-2 mb-4" onsubmit={|evt: yew::SubmitEvent| evt.prevent_default()}>
+                <div class="col-md-8">
+                    <label for="claims-search" class="visually-hidden">{ "Search claims" }</label>
+                    <input
+                        type="search"
+                        id="claims-search"
+                        class="form-control"
+                        placeholder="Search by claim name or description"
+                        oninput={link.callback(|evt| ClaimsDictionaryMessage::query_set(&evt))} />
+                </div>
+                <div class="col-md-4">
+                    <label for="claims-category" class="visually-hidden">{ "Category" }</label>
+                    <select
+                        id="claims-category"
+                        class="form-select"
+                        onchange={link.callback(|evt| ClaimsDictionaryMessage::category_set(&evt))}>
+                        <option value="" selected=true>{ "All categories" }</option>
+                        { options }
+                    </select>
+                </div>
+            </form>
+        }
+    }
+
+    fn view_claim(original_name: &str, claim: &StandardClaim) -> Html {
+        let field = &claim.field;
+        let anchor_id = format!("claim-{original_name}");
+        html! {
+            <div class="row mb-2">
+                <div id={anchor_id.clone()} class="col-md-4 col-lg-3">
+                    <label><strong>{ field.name }</strong></label>
+                    { " " }
+                    <span
+                        class="badge bg-info text-dark fw-normal"
+                        title="Name of the claim field in claims object">
+                        { original_name }
+                    </span>
+                    { if let Some(link) = field.link {
+                        html! {
+                            <>
+                                { " " }
+                                <a href={link}
+                                    class="text-decoration-none"
+                                    title="View field definition">
+                                    { Icon::Link.view() }
+                                </a>
+                            </>
+                        }
+                    } else {
+                        html!{}
+                    }}
+                    { Self::view_anchor(&anchor_id, "Link to this claim") }
+                </div>
+                <div class="col-md-8 col-lg-9">
+                    <div class="mb-1">{ str_to_html(field.description) }</div>
+                    { if let Some(format) = claim.format {
+                        html! {
+                            <div class="text-muted small">
+                                <span class="text-decoration-underline">{ "Format" }</span>
+                                { ": " }
+                                { str_to_html(format) }
+                            </div>
+                        }
+                    } else {
+                        html!{}
+                    }}
+                </div>
+            </div>
+        }
+    }
+
+    fn view_category(
+        id: &str,
+        category: &ClaimCategory,
+        claims: &[&(&'static str, StandardClaim)],
+    ) -> Html {
+        let anchor_id = format!("category-{id}");
+        html! {
+            <section class="claim-category">
+                <h3 id={anchor_id.clone()}>
+                    { category.title }
+                    { Self::view_anchor(&anchor_id, "Link to this category") }
+                </h3>
+                <p>{ str_to_html(category.description) }</p>
+                { for claims.iter().map(|(name, claim)| Self::view_claim(name, claim)) }
+            </section>
+        }
+    }
+}
+
+impl Component for ClaimsDictionary {
+    type Message = ClaimsDictionaryMessage;
+    type Properties = ();
+
+    fn create(_: &Context<Self>) -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, _: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            ClaimsDictionaryMessage::SetQuery(query) => {
+                self.query = query.trim().to_lowercase();
+            }
+            ClaimsDictionaryMessage::SetCategory(category) => {
+                self.category = category;
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let categories: Vec<_> = ClaimCategory::all()
+            .iter()
+            .filter(|(id, _)| self.category.map_or(true, |selected| selected == *id))
+            .filter_map(|(id, category)| {
+                let claims: Vec<_> = StandardClaim::all()
+                    .iter()
+                    .filter(|(name, claim)| claim.category == *id && self.matches(name, claim))
+                    .collect();
+                (!claims.is_empty()).then(|| Self::view_category(id, category, &claims))
+            })
+            .collect();
+
+        html! {
+            <>
+                { Self::view_filters(ctx) }
+                { if categories.is_empty() {
+                    html! {
+                        <p id="claims-not-found" class="text-muted">
+                            { "No claims match the search criteria." }
+                        </p>
+                    }
+                } else {
+                    categories.into_iter().collect::<Html>()
+                }}
+            </>
+        }
+    }
+
+    fn rendered(&mut self, _: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+        // The dictionary is rendered after the page has loaded, so the browser cannot scroll
+        // to the anchor specified in the URL by itself.
+        let window = web_sys::window().expect_throw("no Window");
+        let hash = window.location().hash().unwrap_or_default();
+        let Some(id) = hash.strip_prefix('#').filter(|id| !id.is_empty()) else {
+            return;
+        };
+        let document = window.document().expect_throw("no Document");
+        if let Some(element) = document.get_element_by_id(id) {
+            element.scroll_into_view();
+        }
+    }
+}
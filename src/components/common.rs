@@ -95,6 +95,17 @@ impl FieldWithValue {
                     <div class="text-muted small toggled-description">
                         { str_to_html(field.description) }
                     </div>
+                    { if let Some(format) = self.format {
+                        html! {
+                            <div class="text-muted small toggled-description claim-format">
+                                <span class="text-decoration-underline">{ "Format" }</span>
+                                { ": " }
+                                { str_to_html(format) }
+                            </div>
+                        }
+                    } else {
+                        html!{}
+                    }}
                 </>
             },
         )
@@ -129,6 +140,24 @@ pub fn view_wide_data_row(label: Html, value: Html) -> Html {
     }
 }
 
+/// Renders a code snippet with a button copying its content to the clipboard.
+pub fn view_code_snippet(id: &str, copy_title: &str, content: &str) -> Html {
+    html! {
+        <div class="code-snippet">
+            <div class="code-snippet-panel">
+                <button
+                    type="button"
+                    title={copy_title.to_owned()}
+                    data-clipboard-target={format!("#{id}")}
+                    class="btn btn-sm btn-outline-primary btn-copy">
+                    { "Copy" }
+                </button>
+            </div>
+            <pre><code id={id.to_owned()}>{ content }</code></pre>
+        </div>
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Alert {
     Info,
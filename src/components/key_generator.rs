@@ -0,0 +1,192 @@
+//! Form for generating random key pairs.
+
+use jwt_compact::jwk::JsonWebKey;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{Event, HtmlSelectElement};
+use yew::{html, Callback, Component, Context, Html, Properties};
+
+use super::common::{view_code_snippet, view_wide_data_row};
+use crate::{
+    fields::Field,
+    keys::{KeyPairType, SigningKeyInstance},
+};
+
+/// Generated key pair with both keys serialized in the JWK format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedKeyPair {
+    /// SHA-256 key thumbprint as per RFC 7638. Used as the `kid` field in both JWKs.
+    pub key_id: String,
+    pub private_jwk: String,
+    pub public_jwk: String,
+}
+
+impl GeneratedKeyPair {
+    const KEY_ID_FIELD: Field = Field {
+        name: "Key ID",
+        description: "SHA-256 key thumbprint as defined in RFC 7638. It is included \
+            as the <code>kid</code> field into both keys.",
+        link: Some("https://tools.ietf.org/html/rfc7638"),
+    };
+
+    pub fn generate(key_type: KeyPairType) -> Self {
+        let signing_key = SigningKeyInstance::generate(key_type);
+        let verifying_key = signing_key.to_verifying_key();
+        let key_id = verifying_key.thumbprint();
+        let private_jwk = Self::serialize_jwk(&signing_key.to_jwk(), &key_id);
+        let public_jwk = Self::serialize_jwk(&verifying_key.to_jwk(), &key_id);
+
+        Self {
+            key_id,
+            private_jwk,
+            public_jwk,
+        }
+    }
+
+    fn serialize_jwk(jwk: &JsonWebKey<'_>, key_id: &str) -> String {
+        let mut jwk = serde_json::to_value(jwk).expect_throw("cannot serialize key");
+        let fields = jwk.as_object_mut().expect_throw("key is not an object");
+        fields.insert("kid".to_owned(), key_id.into());
+        serde_json::to_string_pretty(&jwk).expect_throw("cannot serialize key")
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <>
+                { Self::KEY_ID_FIELD.with_code_value(&self.key_id).view_aux() }
+                <h5 class="mt-3">{ "Public key" }</h5>
+                { view_code_snippet(
+                    "generated-public-key",
+                    "Copy public key to clipboard",
+                    &self.public_jwk,
+                ) }
+                <h5 class="mt-3">{ "Private key" }</h5>
+                <p class="text-muted small">
+                    { "The private key is only stored in the browser memory. It should not be \
+                       shared with anyone." }
+                </p>
+                { view_code_snippet(
+                    "generated-private-key",
+                    "Copy private key to clipboard",
+                    &self.private_jwk,
+                ) }
+            </>
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeyGeneratorMessage {
+    SetKeyType(KeyPairType),
+    Generate,
+}
+
+impl KeyGeneratorMessage {
+    fn key_type_set(event: &Event) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for key type set event");
+        let target = target
+            .dyn_into::<HtmlSelectElement>()
+            .expect_throw("unexpected target for key type set event");
+        let key_type = KeyPairType::from_value(&target.value()).unwrap_or_default();
+        Self::SetKeyType(key_type)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Properties)]
+pub struct KeyGeneratorProperties {
+    /// Called with each generated key pair.
+    #[prop_or_default]
+    pub onchange: Callback<GeneratedKeyPair>,
+}
+
+#[derive(Debug)]
+pub struct KeyGenerator {
+    key_type: KeyPairType,
+    key_pair: Option<GeneratedKeyPair>,
+}
+
+impl Component for KeyGenerator {
+    type Message = KeyGeneratorMessage;
+    type Properties = KeyGeneratorProperties;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            key_type: KeyPairType::default(),
+            key_pair: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            KeyGeneratorMessage::SetKeyType(key_type) => {
+                self.key_type = key_type;
+            }
+            KeyGeneratorMessage::Generate => {
+                let key_pair = GeneratedKeyPair::generate(self.key_type);
+                ctx.props().onchange.emit(key_pair.clone());
+                self.key_pair = Some(key_pair);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let options: Html = KeyPairType::ALL
+            .into_iter()
+            .map(|key_type| {
+                html! {
+                    <option
+                        value={key_type.value()}
+                        selected={key_type == self.key_type}>
+                        { key_type.label() }
+                    </option>
+                }
+            })
+            .collect();
+
+        let row = view_wide_data_row(
+            html! {
+                <label for="key-pair-type">
+                    <strong>{ "Key type" }</strong>
+                </label>
+            },
+            html! {
+                <>
+                    <div class="input-group mb-1">
+                        <select
+                            id="key-pair-type"
+                            class="form-select"
+                            onchange={link.callback(|evt| KeyGeneratorMessage::key_type_set(&evt))}>
+                            { options }
+                        </select>
+                        <button
+                            type="button"
+                            class="btn btn-primary"
+                            onclick={link.callback(|_| KeyGeneratorMessage::Generate)}>
+                            { "Generate" }
+                        </button>
+                    </div>
+                    <div class="form-text">
+                        { "Keys are generated in the browser using its cryptographically secure \
+                           RNG. The public key is loaded into the verifying key input, and \
+                           the private key into the signing key input. Generating RSA keys \
+                           may take several seconds." }
+                    </div>
+                </>
+            },
+        );
+
+        html! {
+            <>
+                <form class="mb-4">{ row }</form>
+                { if let Some(key_pair) = &self.key_pair {
+                    key_pair.view()
+                } else {
+                    html!{}
+                }}
+            </>
+        }
+    }
+}
@@ -1,29 +1,183 @@
 //! Row with the JSON web key input.
 
-// TODO: base64/hex key input + type (symmetric | ed25519 | k256)
-
-use base64ct::{Base64UrlUnpadded, Encoding};
-use jwt_compact::jwk::{JsonWebKey, JwkError};
+use base64ct::{Base64, Base64Unpadded, Base64Url, Base64UrlUnpadded, Encoding};
+use jwt_compact::{
+    alg::SecretBytes,
+    jwk::{JsonWebKey, JwkError},
+};
 use sha2::Sha256;
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
-use web_sys::{HtmlTextAreaElement, InputEvent};
+use web_sys::{Event, HtmlSelectElement, HtmlTextAreaElement, InputEvent};
 use yew::{classes, html, Callback, Component, Context, Html, Properties};
 
 use std::fmt;
 
-use super::common::{view_wide_data_row, ComponentRef, Icon, SavedStateManager};
-use crate::{fields::Field, keys::KeyInstance};
+use super::common::{view_data_row, view_wide_data_row, ComponentRef, Icon, SavedStateManager};
+use crate::{
+    fields::Field,
+    jwe::DecryptionError,
+    keys::{KeyInstance, KeySet, KeySetEntry, SigningKeyInstance},
+    pem::{self, PemError, PemFormat},
+    x509::{self, Certificate, CertificateChain, CertificateError, CertificateThumbprints},
+};
+
+/// Key input format selected by the user.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyInputFormat {
+    /// JSON web key or a PEM-encoded public key (the format is detected automatically).
+    #[default]
+    Jwk,
+    /// Hex-encoded symmetric secret.
+    Hex,
+    /// Symmetric secret encoded using the standard base64 alphabet.
+    Base64,
+    /// Symmetric secret encoded using the URL-safe base64 alphabet.
+    Base64Url,
+    /// Symmetric secret provided as UTF-8 text.
+    Utf8,
+}
+
+impl KeyInputFormat {
+    const ALL: [Self; 5] = [
+        Self::Jwk,
+        Self::Hex,
+        Self::Base64,
+        Self::Base64Url,
+        Self::Utf8,
+    ];
+
+    fn value(self) -> &'static str {
+        match self {
+            Self::Jwk => "jwk",
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::Base64Url => "base64url",
+            Self::Utf8 => "utf8",
+        }
+    }
+
+    fn from_value(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.value() == value)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Jwk => "JWK / PEM",
+            Self::Hex => "Hex secret",
+            Self::Base64 => "Base64 secret",
+            Self::Base64Url => "Base64url secret",
+            Self::Utf8 => "UTF-8 passphrase",
+        }
+    }
+
+    /// Decodes a symmetric secret. Must not be called for `Self::Jwk`.
+    fn decode_secret(self, raw_key: &str) -> Result<Vec<u8>, KeyParseError> {
+        // Whitespace is insignificant for binary-to-text encodings, and may be introduced
+        // by copy-pasting.
+        let compact_key: String = raw_key
+            .chars()
+            .filter(|ch| !ch.is_ascii_whitespace())
+            .collect();
+        let secret = match self {
+            Self::Jwk => unreachable!(),
+            Self::Hex => base16ct::mixed::decode_vec(&compact_key).map_err(KeyParseError::Hex)?,
+            Self::Base64 => if compact_key.ends_with('=') {
+                Base64::decode_vec(&compact_key)
+            } else {
+                Base64Unpadded::decode_vec(&compact_key)
+            }
+            .map_err(KeyParseError::Base64)?,
+            Self::Base64Url => if compact_key.ends_with('=') {
+                Base64Url::decode_vec(&compact_key)
+            } else {
+                Base64UrlUnpadded::decode_vec(&compact_key)
+            }
+            .map_err(KeyParseError::Base64)?,
+            Self::Utf8 => raw_key.as_bytes().to_vec(),
+        };
+
+        if secret.is_empty() {
+            Err(KeyParseError::EmptySecret)
+        } else {
+            Ok(secret)
+        }
+    }
+}
+
+/// Intended usage of the key, which depends on the token kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyUsage {
+    /// Verifying signed tokens.
+    #[default]
+    Verification,
+    /// Decrypting encrypted tokens. Requires private keys for asymmetric key types.
+    Decryption,
+}
+
+/// Format in which the key is provided.
+#[derive(Debug, Clone, Copy)]
+enum KeyFormat {
+    Jwk,
+    Jwks,
+    Pem(PemFormat),
+    Certificate,
+    Secret(KeyInputFormat),
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Jwk => formatter.write_str("JSON Web Key"),
+            Self::Jwks => formatter.write_str("JSON Web Key Set"),
+            Self::Pem(format) => fmt::Display::fmt(format, formatter),
+            Self::Certificate => formatter.write_str("PEM (X.509 certificate)"),
+            Self::Secret(format) => formatter.write_str(match format {
+                KeyInputFormat::Hex => "Hex-encoded secret",
+                KeyInputFormat::Base64 => "Base64-encoded secret",
+                KeyInputFormat::Base64Url => "Base64url-encoded secret",
+                KeyInputFormat::Utf8 => "UTF-8 passphrase",
+                KeyInputFormat::Jwk => unreachable!(),
+            }),
+        }
+    }
+}
+
+/// Errors that can occur when parsing a key.
+#[derive(Debug)]
+enum KeyParseError {
+    Jwk(serde_json::Error),
+    Pem(PemError),
+    Certificate(CertificateError),
+    Hex(base16ct::Error),
+    Base64(base64ct::Error),
+    EmptySecret,
+    EmptyKeySet,
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Jwk(err) => fmt::Display::fmt(err, formatter),
+            Self::Pem(err) => fmt::Display::fmt(err, formatter),
+            Self::Certificate(err) => fmt::Display::fmt(err, formatter),
+            Self::Hex(err) => write!(formatter, "invalid hex-encoded secret: {err}"),
+            Self::Base64(err) => write!(formatter, "invalid base64-encoded secret: {err}"),
+            Self::EmptySecret => formatter.write_str("secret is empty"),
+            Self::EmptyKeySet => formatter.write_str("key set is empty"),
+        }
+    }
+}
 
 /// Key type together with auxiliary information.
 #[derive(Debug)]
-enum ExtendedKeyType {
+pub(super) enum ExtendedKeyType {
     Rsa { bits: usize },
     Symmetric { bytes: usize },
     EllipticCurve { curve_name: String },
 }
 
 impl ExtendedKeyType {
-    fn new(jwk: &JsonWebKey<'_>) -> Self {
+    pub(super) fn new(jwk: &JsonWebKey<'_>) -> Self {
         match jwk {
             JsonWebKey::Rsa { modulus, .. } => Self::Rsa {
                 bits: modulus.len() * 8,
@@ -57,12 +211,55 @@ impl fmt::Display for ExtendedKeyType {
     }
 }
 
+/// Key from the key input together with its metadata.
+#[derive(Debug)]
+struct RawKey {
+    key_id: Option<String>,
+    jwk: JsonWebKey<'static>,
+    /// Thumbprints of the key certificate, or an error parsing the certificate.
+    certificate: Option<Result<CertificateThumbprints, CertificateError>>,
+}
+
+impl RawKey {
+    fn new(jwk: JsonWebKey<'static>) -> Self {
+        Self {
+            key_id: None,
+            jwk,
+            certificate: None,
+        }
+    }
+
+    /// Creates a key with metadata (`kid` and `x5c` fields) from the JSON presentation
+    /// of the JWK. As per RFC 7517, section 4.7, the key certificate is the first one
+    /// in the `x5c` chain.
+    fn with_metadata(jwk: JsonWebKey<'static>, value: &serde_json::Value) -> Self {
+        let key_id = value
+            .get("kid")
+            .and_then(|id| id.as_str().map(str::to_owned));
+        let certificate = value.get("x5c").map(|chain| {
+            CertificateChain::from_header(chain).map(|chain| chain.leaf().thumbprints())
+        });
+        Self {
+            key_id,
+            jwk,
+            certificate,
+        }
+    }
+}
+
+/// Result of parsing a key from the key input. Keys in a key set are parsed individually.
+type KeyParseResult = Result<RawKey, serde_json::Error>;
+
 #[derive(Debug)]
 struct ParsedKey {
+    key_id: Option<String>,
     key_type: ExtendedKeyType,
     is_signing_key: bool,
     sha256_thumbprint: [u8; 32],
+    certificate: Option<Result<CertificateThumbprints, CertificateError>>,
     instantiate_result: Result<(), JwkError>,
+    /// Result of instantiating the private key; `Ok(false)` if the key has no private parts.
+    private_key_result: Result<bool, JwkError>,
 }
 
 impl ParsedKey {
@@ -74,6 +271,13 @@ impl ParsedKey {
         link: None,
     };
 
+    const KEY_ID_FIELD: Field = Field {
+        name: "Key ID",
+        description: "Identifier of the key. If the token header contains the same \
+            <code>kid</code>, this key is used for verification.",
+        link: Some("https://www.rfc-editor.org/rfc/rfc7517.html#section-4.5"),
+    };
+
     const THUMBPRINT_FIELD: Field = Field {
         name: "Thumbprint (SHA-256)",
         description: "As defined in RFC 7638, a key thumbprint is computed by hashing its \
@@ -82,6 +286,14 @@ impl ParsedKey {
         link: Some("https://tools.ietf.org/html/rfc7638"),
     };
 
+    const CERTIFICATE_FIELD: Field = Field {
+        name: "Certificate thumbprints",
+        description: "SHA-1 and SHA-256 thumbprints of the X.509 certificate for the key. \
+            If the token header contains <code>x5t</code> or <code>x5t#S256</code> fields, \
+            they are compared with these thumbprints after verification.",
+        link: Some("https://www.rfc-editor.org/rfc/rfc7515.html#section-4.1.7"),
+    };
+
     const KEY_USAGE_FIELD: Field = Field {
         name: "Usage",
         description: "JWK for a signing key is always valid as a JWK for the corresponding \
@@ -89,20 +301,162 @@ impl ParsedKey {
         link: None,
     };
 
-    fn view(&self) -> Html {
-        let should_warn =
-            self.is_signing_key && !matches!(self.key_type, ExtendedKeyType::Symmetric { .. });
-        let thumbprint = Base64UrlUnpadded::encode_string(&self.sha256_thumbprint);
+    fn new(index: usize, raw_key: RawKey) -> (Self, Option<KeySetEntry>) {
+        let RawKey {
+            key_id,
+            jwk,
+            certificate,
+        } = raw_key;
+        let jwk = &jwk;
+        let mut sha256_thumbprint = [0_u8; 32];
+        sha256_thumbprint.copy_from_slice(&jwk.thumbprint::<Sha256>());
+
+        let (entry, instantiate_result) = match KeyInstance::new(jwk) {
+            Ok(key) => (Some(KeySetEntry::new(index, key_id.clone(), key)), Ok(())),
+            Err(err) => (None, Err(err)),
+        };
+
+        let is_signing_key = jwk.is_signing_key();
+        let (entry, private_key_result) = match entry {
+            Some(entry) if entry.private_key.is_some() => (Some(entry), Ok(true)),
+            Some(entry) if is_signing_key => match SigningKeyInstance::new(jwk) {
+                Ok(private_key) => (Some(entry.with_private_key(private_key)), Ok(true)),
+                Err(err) => (Some(entry), Err(err)),
+            },
+            entry => (entry, Ok(false)),
+        };
+        let entry = match &certificate {
+            Some(Ok(thumbprints)) => entry.map(|entry| entry.with_certificate(thumbprints.clone())),
+            _ => entry,
+        };
+
+        let this = Self {
+            key_id,
+            key_type: ExtendedKeyType::new(jwk),
+            is_signing_key,
+            sha256_thumbprint,
+            certificate,
+            instantiate_result,
+            private_key_result,
+        };
+        (this, entry)
+    }
+
+    fn should_warn_about_usage(&self, usage: KeyUsage) -> bool {
+        usage == KeyUsage::Verification
+            && self.is_signing_key
+            && !matches!(self.key_type, ExtendedKeyType::Symmetric { .. })
+    }
+
+    fn error(&self, usage: KeyUsage) -> Option<String> {
+        if let Err(err) = &self.instantiate_result {
+            return Some(err.to_string());
+        }
+        if usage == KeyUsage::Decryption {
+            match &self.private_key_result {
+                Ok(true) => {}
+                Ok(false) => return Some(DecryptionError::NoPrivateKey.to_string()),
+                Err(err) => return Some(format!("invalid private key: {err}")),
+            }
+        }
+        None
+    }
+
+    fn thumbprint(&self) -> String {
+        Base64UrlUnpadded::encode_string(&self.sha256_thumbprint)
+    }
 
+    fn view(&self, usage: KeyUsage) -> Html {
         html! {
             <>
-                { if should_warn {
+                { if self.should_warn_about_usage(usage) {
                     Self::view_signing_key_warning()
                 } else {
                     html!{}
                 }}
                 { Self::KEY_FIELD.with_value(&self.key_type).view_aux() }
-                { Self::THUMBPRINT_FIELD.with_code_value(&thumbprint).view_aux() }
+                { if let Some(key_id) = &self.key_id {
+                    Self::KEY_ID_FIELD.with_code_value(key_id).view_aux()
+                } else {
+                    html!{}
+                }}
+                { Self::THUMBPRINT_FIELD.with_code_value(&self.thumbprint()).view_aux() }
+                { if let Some(certificate) = &self.certificate {
+                    Self::CERTIFICATE_FIELD
+                        .with_html_value(Self::view_certificate(certificate))
+                        .view_aux()
+                } else {
+                    html!{}
+                }}
+            </>
+        }
+    }
+
+    fn view_certificate(certificate: &Result<CertificateThumbprints, CertificateError>) -> Html {
+        match certificate {
+            Ok(thumbprints) => html! {
+                <>
+                    <div class="small">
+                        { "SHA-1: " }<code class="text-break-all">{ &thumbprints.sha1 }</code>
+                    </div>
+                    <div class="small">
+                        { "SHA-256: " }<code class="text-break-all">{ &thumbprints.sha256 }</code>
+                    </div>
+                </>
+            },
+            Err(err) => html! {
+                <div class="small text-danger">{ "Invalid certificate: " }{ err }</div>
+            },
+        }
+    }
+
+    /// Renders a compact summary of the key for a key set entry.
+    fn view_summary(&self, usage: KeyUsage) -> Html {
+        html! {
+            <>
+                <div>
+                    { self.key_type.to_string() }
+                    { if self.should_warn_about_usage(usage) {
+                        html! {
+                            <span
+                                class="badge bg-warning text-dark ms-2"
+                                title="Potentially incorrect key usage!">
+                                { Icon::Warning.view() }{ " signing" }
+                            </span>
+                        }
+                    } else {
+                        html!{}
+                    }}
+                </div>
+                { if let Some(key_id) = &self.key_id {
+                    html! {
+                        <div class="small">
+                            { "Key ID: " }<code>{ key_id }</code>
+                        </div>
+                    }
+                } else {
+                    html!{}
+                }}
+                <div class="small">
+                    { "Thumbprint (SHA-256): " }<code>{ self.thumbprint() }</code>
+                </div>
+                { match &self.certificate {
+                    Some(Ok(thumbprints)) => html! {
+                        <div class="small">
+                            { "Certificate thumbprint (SHA-256): " }
+                            <code>{ &thumbprints.sha256 }</code>
+                        </div>
+                    },
+                    Some(Err(err)) => html! {
+                        <div class="small text-danger">{ "Invalid certificate: " }{ err }</div>
+                    },
+                    None => html!{},
+                }}
+                { if let Some(err) = self.error(usage) {
+                    html! { <div class="small text-danger">{ err }</div> }
+                } else {
+                    html!{}
+                }}
             </>
         }
     }
@@ -122,63 +476,206 @@ impl ParsedKey {
     }
 }
 
+/// One or more keys parsed from the key input.
+#[derive(Debug)]
+struct ParsedKeys {
+    format: KeyFormat,
+    keys: Vec<Result<ParsedKey, serde_json::Error>>,
+}
+
+impl ParsedKeys {
+    const FORMAT_FIELD: Field = Field {
+        name: "Format",
+        description: "Public keys in the PEM format and raw symmetric secrets are converted \
+            to the equivalent <abbr title=\"JSON web key\">JWK</abbr>; key properties below \
+            (e.g., the thumbprint) are computed for this JWK.",
+        link: None,
+    };
+
+    const KEY_SET_FIELD: Field = Field {
+        name: "Format",
+        description: "If the token header specifies a key ID (<code>kid</code>) matching \
+            the key ID or the thumbprint of a key in the set, only this key is used for \
+            verification. Otherwise, all keys compatible with the token algorithm are tried.",
+        link: Some("https://www.rfc-editor.org/rfc/rfc7517.html#section-5"),
+    };
+
+    fn error(&self, usage: KeyUsage) -> Option<String> {
+        if let KeyFormat::Jwks = self.format {
+            let has_valid_keys = self
+                .keys
+                .iter()
+                .any(|key| key.as_ref().is_ok_and(|key| key.error(usage).is_none()));
+            (!has_valid_keys).then(|| match usage {
+                KeyUsage::Verification => "key set contains no supported keys".to_owned(),
+                KeyUsage::Decryption => "key set contains no supported decryption keys".to_owned(),
+            })
+        } else {
+            match self.keys.first() {
+                Some(Ok(key)) => key.error(usage),
+                Some(Err(err)) => Some(err.to_string()),
+                None => None,
+            }
+        }
+    }
+
+    fn view(&self, usage: KeyUsage) -> Html {
+        if let KeyFormat::Jwks = self.format {
+            let format = format!("{} ({} keys)", self.format, self.keys.len());
+            let keys: Html = self
+                .keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| Self::view_key_set_entry(i, key, usage))
+                .collect();
+            html! {
+                <>
+                    { Self::KEY_SET_FIELD.with_value(&format).view_aux() }
+                    { keys }
+                </>
+            }
+        } else {
+            html! {
+                <>
+                    { Self::FORMAT_FIELD.with_value(&self.format).view_aux() }
+                    { if let Some(Ok(key)) = self.keys.first() {
+                        key.view(usage)
+                    } else {
+                        html!{}
+                    }}
+                </>
+            }
+        }
+    }
+
+    fn view_key_set_entry(
+        index: usize,
+        key: &Result<ParsedKey, serde_json::Error>,
+        usage: KeyUsage,
+    ) -> Html {
+        let value = match key {
+            Ok(key) => key.view_summary(usage),
+            Err(err) => html! {
+                <>
+                    <div>{ "Invalid key" }</div>
+                    <div class="small text-danger">{ err.to_string() }</div>
+                </>
+            },
+        };
+        view_data_row(
+            html! {
+                <div class="ps-md-2">
+                    <label>{ format!("Key #{}", index + 1) }</label>
+                </div>
+            },
+            value,
+        )
+    }
+}
+
 #[derive(Debug)]
 struct KeyInputState {
     raw_key: String,
-    parse_result: Result<Option<ParsedKey>, serde_json::Error>,
+    format: KeyInputFormat,
+    parse_result: Result<Option<ParsedKeys>, KeyParseError>,
 }
 
 impl Default for KeyInputState {
     fn default() -> Self {
         Self {
             raw_key: String::new(),
+            format: KeyInputFormat::default(),
             parse_result: Ok(None),
         }
     }
 }
 
 impl KeyInputState {
-    fn new(raw_key: String) -> (Self, Option<KeyInstance>) {
-        let jwk = match serde_json::from_str::<JsonWebKey<'_>>(&raw_key) {
-            Ok(jwk) => jwk,
+    fn new(raw_key: String, input_format: KeyInputFormat) -> (Self, Option<KeySet>) {
+        let (format, jwks) = match Self::parse_jwks(&raw_key, input_format) {
+            Ok(parsed) => parsed,
             Err(err) => {
                 let this = Self {
                     raw_key,
+                    format: input_format,
                     parse_result: Err(err),
                 };
                 return (this, None);
             }
         };
 
-        let mut sha256_thumbprint = [0_u8; 32];
-        sha256_thumbprint.copy_from_slice(&jwk.thumbprint::<Sha256>());
-
-        let (key_instance, instantiate_result) = match KeyInstance::new(&jwk) {
-            Ok(key) => (Some(key), Ok(())),
-            Err(err) => (None, Err(err)),
-        };
+        let mut key_set_entries = vec![];
+        let keys = jwks
+            .into_iter()
+            .enumerate()
+            .map(|(index, parse_result)| {
+                let (parsed_key, entry) = ParsedKey::new(index, parse_result?);
+                key_set_entries.extend(entry);
+                Ok(parsed_key)
+            })
+            .collect();
 
         let this = Self {
             raw_key,
-            parse_result: Ok(Some(ParsedKey {
-                key_type: ExtendedKeyType::new(&jwk),
-                is_signing_key: jwk.is_signing_key(),
-                sha256_thumbprint,
-                instantiate_result,
-            })),
+            format: input_format,
+            parse_result: Ok(Some(ParsedKeys { format, keys })),
         };
-        (this, key_instance)
+        (this, KeySet::new(key_set_entries))
+    }
+
+    /// Parses the raw input into one or more JWKs.
+    fn parse_jwks(
+        raw_key: &str,
+        input_format: KeyInputFormat,
+    ) -> Result<(KeyFormat, Vec<KeyParseResult>), KeyParseError> {
+        if input_format != KeyInputFormat::Jwk {
+            let secret = input_format.decode_secret(raw_key)?;
+            let jwk = JsonWebKey::Symmetric {
+                secret: SecretBytes::owned(secret),
+            };
+            return Ok((KeyFormat::Secret(input_format), vec![Ok(RawKey::new(jwk))]));
+        } else if x509::is_pem_certificate(raw_key) {
+            let certificate = Certificate::from_pem(raw_key).map_err(KeyParseError::Certificate)?;
+            let thumbprints = certificate.thumbprints();
+            let jwk = certificate.into_public_key().map_err(KeyParseError::Pem)?;
+            let key = RawKey {
+                certificate: Some(Ok(thumbprints)),
+                ..RawKey::new(jwk)
+            };
+            return Ok((KeyFormat::Certificate, vec![Ok(key)]));
+        } else if pem::is_pem(raw_key) {
+            let (jwk, format) = pem::parse_public_key(raw_key).map_err(KeyParseError::Pem)?;
+            return Ok((KeyFormat::Pem(format), vec![Ok(RawKey::new(jwk))]));
+        }
+
+        let value: serde_json::Value = serde_json::from_str(raw_key).map_err(KeyParseError::Jwk)?;
+        let key_set = value.as_object().and_then(|obj| obj.get("keys"));
+        if let Some(keys) = key_set {
+            let keys: Vec<serde_json::Value> =
+                serde_json::from_value(keys.clone()).map_err(KeyParseError::Jwk)?;
+            if keys.is_empty() {
+                return Err(KeyParseError::EmptyKeySet);
+            }
+            let keys = keys
+                .into_iter()
+                .map(|key| {
+                    let jwk = serde_json::from_value(key.clone())?;
+                    Ok(RawKey::with_metadata(jwk, &key))
+                })
+                .collect();
+            Ok((KeyFormat::Jwks, keys))
+        } else {
+            // Parse the key from the string once again to get more informative errors.
+            let jwk = serde_json::from_str(raw_key).map_err(KeyParseError::Jwk)?;
+            Ok((KeyFormat::Jwk, vec![Ok(RawKey::with_metadata(jwk, &value))]))
+        }
     }
 
-    fn error(&self) -> Option<&dyn fmt::Display> {
+    fn error(&self, usage: KeyUsage) -> Option<String> {
         match &self.parse_result {
-            Err(err) => Some(err),
-            Ok(Some(key)) => key
-                .instantiate_result
-                .as_ref()
-                .err()
-                .map(|err| err as &dyn fmt::Display),
-            _ => None,
+            Err(err) => Some(err.to_string()),
+            Ok(Some(keys)) => keys.error(usage),
+            Ok(None) => None,
         }
     }
 }
@@ -186,9 +683,21 @@ impl KeyInputState {
 #[derive(Debug)]
 pub enum KeyInputMessage {
     SetKey(String),
+    SetFormat(KeyInputFormat),
 }
 
 impl KeyInputMessage {
+    fn format_set(event: &Event) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for key format set event");
+        let target = target
+            .dyn_into::<HtmlSelectElement>()
+            .expect_throw("unexpected target for key format set event");
+        let format = KeyInputFormat::from_value(&target.value()).unwrap_or_default();
+        Self::SetFormat(format)
+    }
+
     fn key_set(event: &InputEvent) -> Self {
         let target = event.target().expect_throw("no target for key set event");
         let target = target
@@ -203,15 +712,20 @@ pub struct KeyInputProperties {
     #[prop_or_default]
     pub component_ref: ComponentRef<KeyInput>,
     #[prop_or_default]
-    pub onchange: Callback<Option<KeyInstance>>,
+    pub onchange: Callback<Option<KeySet>>,
     #[prop_or_default]
     pub save: bool,
+    /// Expected key usage. Only influences how the key is displayed; the emitted key set
+    /// always contains private keys if they are present in the input.
+    #[prop_or_default]
+    pub usage: KeyUsage,
 }
 
 #[derive(Debug)]
 pub struct KeyInput {
     state: KeyInputState,
     state_manager: SavedStateManager,
+    format_state_manager: SavedStateManager,
 }
 
 impl Component for KeyInput {
@@ -222,11 +736,17 @@ impl Component for KeyInput {
         ctx.props().component_ref.link_with(ctx.link().clone());
         let (state_manager, init_state) =
             SavedStateManager::new(Self::STORAGE_KEY, ctx.props().save);
+        let (format_state_manager, init_format) =
+            SavedStateManager::new(Self::FORMAT_STORAGE_KEY, ctx.props().save);
 
         let mut this = Self {
             state: KeyInputState::default(),
             state_manager,
+            format_state_manager,
         };
+        if let Some(format) = init_format.as_deref().and_then(KeyInputFormat::from_value) {
+            this.state.format = format;
+        }
         if let Some(key) = init_state {
             this.update(ctx, KeyInputMessage::SetKey(key));
         }
@@ -237,7 +757,17 @@ impl Component for KeyInput {
         match message {
             KeyInputMessage::SetKey(key) => {
                 self.state_manager.save(&key);
-                let (new_state, maybe_key) = KeyInputState::new(key);
+                let (new_state, maybe_key) = KeyInputState::new(key, self.state.format);
+                self.state = new_state;
+                ctx.props().onchange.emit(maybe_key);
+            }
+            KeyInputMessage::SetFormat(format) => {
+                if format == self.state.format {
+                    return false;
+                }
+                self.format_state_manager.save(format.value());
+                let raw_key = std::mem::take(&mut self.state.raw_key);
+                let (new_state, maybe_key) = KeyInputState::new(raw_key, format);
                 self.state = new_state;
                 ctx.props().onchange.emit(maybe_key);
             }
@@ -245,11 +775,13 @@ impl Component for KeyInput {
         true
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
         ctx.props().component_ref.link_with(ctx.link().clone());
         self.state_manager.set_save_flag(ctx.props().save);
         self.state_manager.save(&self.state.raw_key);
-        false
+        self.format_state_manager.set_save_flag(ctx.props().save);
+        self.format_state_manager.save(self.state.format.value());
+        ctx.props().usage != old_props.usage
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -260,7 +792,8 @@ impl Component for KeyInput {
             "small",
             "text-break-all"
         ];
-        let err = self.state.error();
+        let usage = ctx.props().usage;
+        let err = self.state.error(usage);
         if err.is_some() {
             control_classes.push("is-invalid");
         }
@@ -268,16 +801,22 @@ impl Component for KeyInput {
         let link = ctx.link();
         let row = view_wide_data_row(
             html! {
-                <label for="key">
-                    <strong>{ "Verifying key" }</strong>
-                </label>
+                <>
+                    <label for="key">
+                        <strong>{ match usage {
+                            KeyUsage::Verification => "Verifying key",
+                            KeyUsage::Decryption => "Decryption key",
+                        }}</strong>
+                    </label>
+                    { self.view_format_select(ctx) }
+                </>
             },
             html! {
                 <>
                     <textarea
                         id="key"
                         class={control_classes}
-                        placeholder="Encoded key"
+                        placeholder={self.placeholder()}
                         autocomplete="off"
                         spellcheck="false"
                         value={self.state.raw_key.clone()}
@@ -285,17 +824,18 @@ impl Component for KeyInput {
                         { &self.state.raw_key }
                     </textarea>
 
-                    { if let Some(err) = err {
+                    { if let Some(err) = &err {
                         Self::view_err(err)
                     } else {
                         html!{}
                     }}
 
-                    <div class="form-text">
-                        { "A key should be provided in the " }
-                        <a href="https://www.rfc-editor.org/rfc/rfc7517.html">{ "JSON Web Key" }</a>
-                        { " format, that is, as a JSON object." }
-                    </div>
+                    { self.view_format_hint() }
+                    { if usage == KeyUsage::Decryption {
+                        Self::view_decryption_hint()
+                    } else {
+                        html!{}
+                    }}
                 </>
             },
         );
@@ -303,8 +843,8 @@ impl Component for KeyInput {
         html! {
             <>
                 { row }
-                { if let Ok(Some(key)) = &self.state.parse_result {
-                    key.view()
+                { if let Ok(Some(keys)) = &self.state.parse_result {
+                    keys.view(usage)
                 } else {
                     html!{}
                 }}
@@ -315,10 +855,91 @@ impl Component for KeyInput {
 
 impl KeyInput {
     const STORAGE_KEY: &'static str = "jwt__rawKey";
+    const FORMAT_STORAGE_KEY: &'static str = "jwt__keyFormat";
+
+    fn placeholder(&self) -> &'static str {
+        match self.state.format {
+            KeyInputFormat::Jwk => "Encoded key",
+            KeyInputFormat::Utf8 => "Secret passphrase",
+            _ => "Encoded secret",
+        }
+    }
+
+    fn view_format_select(&self, ctx: &Context<Self>) -> Html {
+        let options: Html = KeyInputFormat::ALL
+            .into_iter()
+            .map(|format| {
+                html! {
+                    <option
+                        value={format.value()}
+                        selected={format == self.state.format}>
+                        { format.label() }
+                    </option>
+                }
+            })
+            .collect();
+
+        html! {
+            <select
+                id="key-format"
+                class="form-select form-select-sm mt-lg-1 mb-1"
+                title="Key format"
+                aria-label="Key format"
+                onchange={ctx.link().callback(|evt| KeyInputMessage::format_set(&evt))}>
+                { options }
+            </select>
+        }
+    }
+
+    fn view_format_hint(&self) -> Html {
+        if self.state.format == KeyInputFormat::Jwk {
+            html! {
+                <div class="form-text">
+                    { "A key should be provided in the " }
+                    <a href="https://www.rfc-editor.org/rfc/rfc7517.html">{ "JSON Web Key" }</a>
+                    { " format, that is, as a JSON object, as a key set (" }
+                    <code>{ r#"{"keys": [...]}"# }</code>
+                    { "), as a PEM-encoded public key (" }
+                    <code>{ "BEGIN PUBLIC KEY" }</code>
+                    { " or " }
+                    <code>{ "BEGIN RSA PUBLIC KEY" }</code>
+                    { "), or as a PEM-encoded X.509 certificate (" }
+                    <code>{ "BEGIN CERTIFICATE" }</code>
+                    { ")." }
+                </div>
+            }
+        } else {
+            html! {
+                <div class="form-text">
+                    { "The input is decoded into a symmetric secret for " }
+                    <code>{ "HS*" }</code>
+                    { " algorithms. " }
+                    { if self.state.format == KeyInputFormat::Utf8 {
+                        "The passphrase is used verbatim, including any whitespace."
+                    } else {
+                        "Whitespace in the input is ignored."
+                    }}
+                </div>
+            }
+        }
+    }
+
+    fn view_decryption_hint() -> Html {
+        html! {
+            <div class="form-text">
+                { "The token is encrypted. It can be decrypted with a symmetric key (for " }
+                <code>{ "dir" }</code>{ " and " }<code>{ "A*KW" }</code>
+                { " algorithms) or with the private key of the recipient (for " }
+                <code>{ "RSA-OAEP*" }</code>{ " and " }<code>{ "ECDH-ES*" }</code>
+                { " algorithms). To verify a nested signed token, provide a key set containing \
+                   both the decryption key and the verifying key." }
+            </div>
+        }
+    }
 
-    fn view_err(err: &dyn fmt::Display) -> Html {
+    fn view_err(err: &str) -> Html {
         html! {
-            <p class="invalid-feedback mb-1">{ err.to_string() }</p>
+            <p class="invalid-feedback mb-1">{ err }</p>
         }
     }
 }
@@ -1,8 +1,11 @@
 //! Application components.
 
 mod app;
+pub mod claims_dictionary;
 mod common;
+pub mod key_generator;
 pub mod key_input;
 pub mod token_input;
+pub mod token_signer;
 
 pub use self::app::{App, AppMessage, AppProperties};
@@ -1,49 +1,489 @@
 //! Row with the JSON web token input.
 
 use base64ct::{Base64UrlUnpadded, Encoding};
-use jwt_compact::{Header, ParseError, Thumbprint, UntrustedToken};
+use chrono::{DateTime, Utc};
+use jwt_compact::{jwk::JsonWebKey, ParseError, UntrustedToken};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use web_sys::{HtmlTextAreaElement, InputEvent};
-use yew::{classes, html, Callback, Component, Context, Html, Properties};
+use yew::{classes, html, html::Scope, Callback, Component, Context, Html, Properties};
 
-use super::common::{view_wide_data_row, ComponentRef, SavedStateManager};
-use crate::fields::{Field, StandardHeader};
+use std::{fmt, mem};
+
+use super::{
+    common::{
+        view_code_snippet, view_data_row, view_wide_data_row, ComponentRef, Icon, SavedStateManager,
+    },
+    key_input::ExtendedKeyType,
+};
+use crate::{
+    detached::{is_detached_token, DetachedParseError, DetachedToken},
+    fields::{Field, StandardHeader},
+    jwe::{is_jwe, EncryptedToken, JweParseError},
+    jws::{is_json_serialization, JsonParseError, JsonSignature, JsonToken},
+    keys::KeyInstance,
+    nested::{is_nested_content_type, NestingToken},
+    x509::{Certificate, CertificateChain},
+};
+
+/// Token supplied to the token input.
+#[derive(Debug)]
+pub enum InputToken {
+    /// Signed token (JWS).
+    Signed(UntrustedToken<'static>),
+    /// Signed token wrapping another token (`cty: JWT`).
+    Nested(NestingToken),
+    /// Encrypted token (JWE).
+    Encrypted(EncryptedToken),
+    /// Signed token in the JWS JSON serialization, possibly with multiple signatures.
+    Json(JsonToken),
+    /// Signed token with a detached and / or unencoded (`b64: false`) payload.
+    Detached(DetachedToken),
+}
+
+impl InputToken {
+    /// Parses a token in the compact serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed.
+    pub fn new(raw: &str) -> Result<Self, TokenParseError> {
+        if is_json_serialization(raw) {
+            return JsonToken::new(raw)
+                .map(Self::Json)
+                .map_err(TokenParseError::Json);
+        }
+        if is_jwe(raw) {
+            return EncryptedToken::new(raw)
+                .map(Self::Encrypted)
+                .map_err(TokenParseError::Encrypted);
+        }
+
+        if is_detached_token(raw) {
+            return DetachedToken::new(raw)
+                .map(Self::Detached)
+                .map_err(TokenParseError::Detached);
+        }
+
+        match UntrustedToken::new(raw) {
+            Ok(token) => Ok(Self::Signed(token.into_owned())),
+            Err(ParseError::UnsupportedContentType(cty)) if is_nested_content_type(&cty) => {
+                NestingToken::new(raw)
+                    .map(Self::Nested)
+                    .map_err(TokenParseError::Signed)
+            }
+            Err(err) => Err(TokenParseError::Signed(err)),
+        }
+    }
+
+    /// Returns the header for display. `raw` is the string this token was parsed from;
+    /// `root_certificate` is the PEM-encoded root certificate to validate the `x5c` chain against,
+    /// and `evaluated_at` is the validation time for the chain (`None` means the current time).
+    fn header(
+        &self,
+        raw: &str,
+        root_certificate: &str,
+        evaluated_at: Option<DateTime<Utc>>,
+    ) -> TokenHeader {
+        match self {
+            Self::Json(token) => TokenHeader::Json {
+                is_flattened: token.is_flattened(),
+                signatures: token.signatures().to_vec(),
+            },
+            Self::Encrypted(_) => TokenHeader::Compact(Box::new(ParsedHeader::new(
+                raw,
+                true,
+                root_certificate,
+                evaluated_at,
+            ))),
+            Self::Signed(_) | Self::Nested(_) | Self::Detached(_) => {
+                TokenHeader::Compact(Box::new(ParsedHeader::new(
+                    raw,
+                    false,
+                    root_certificate,
+                    evaluated_at,
+                )))
+            }
+        }
+    }
+}
+
+/// Errors that can occur when parsing an [`InputToken`].
+#[derive(Debug)]
+pub enum TokenParseError {
+    Signed(ParseError),
+    Encrypted(JweParseError),
+    Json(JsonParseError),
+    Detached(DetachedParseError),
+}
+
+impl fmt::Display for TokenParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signed(err) => fmt::Display::fmt(err, formatter),
+            Self::Encrypted(err) => fmt::Display::fmt(err, formatter),
+            Self::Json(err) => fmt::Display::fmt(err, formatter),
+            Self::Detached(err) => fmt::Display::fmt(err, formatter),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TokenHeader {
+    Compact(Box<ParsedHeader>),
+    Json {
+        is_flattened: bool,
+        signatures: Vec<JsonSignature>,
+    },
+}
+
+impl TokenHeader {
+    const SERIALIZATION_FIELD: Field = Field {
+        name: "Serialization",
+        description: "JWS JSON serialization allows to sign the same payload with several keys. \
+            Each signature has its own protected header (covered by the signature) and \
+            unprotected header.",
+        link: Some("https://www.rfc-editor.org/rfc/rfc7515.html#section-7.2"),
+    };
+
+    fn view(&self, link: &Scope<TokenInput>) -> Html {
+        match self {
+            Self::Compact(header) => header.view(link),
+            Self::Json {
+                is_flattened,
+                signatures,
+            } => Self::view_json_headers(*is_flattened, signatures),
+        }
+    }
+
+    fn view_json_headers(is_flattened: bool, signatures: &[JsonSignature]) -> Html {
+        let serialization = if is_flattened {
+            "JSON (flattened)"
+        } else {
+            "JSON (general)"
+        };
+        html! {
+            <>
+                { Self::SERIALIZATION_FIELD.with_value(&serialization).view_aux() }
+                { for signatures.iter().enumerate().map(|(i, signature)| {
+                    Self::view_json_signature(i, signature)
+                }) }
+            </>
+        }
+    }
+
+    fn view_json_signature(index: usize, signature: &JsonSignature) -> Html {
+        let label = html! {
+            <div class="ps-md-2">
+                <label class="text-decoration--md-underline">
+                    { format!("Signature #{}", index + 1) }
+                </label>
+            </div>
+        };
+        view_data_row(
+            label,
+            html! {
+                <>
+                    <div>
+                        <code>{ signature.algorithm() }</code>
+                        { if let Some(key_id) = signature.key_id() {
+                            html! { <>{ " (kid: " }<code>{ key_id }</code>{ ")" }</> }
+                        } else {
+                            html!{}
+                        }}
+                    </div>
+                    { Self::view_json_header("Protected header", &signature.protected_header) }
+                    { Self::view_json_header("Unprotected header", &signature.unprotected_header) }
+                </>
+            },
+        )
+    }
+
+    fn view_json_header(title: &str, header: &serde_json::Map<String, serde_json::Value>) -> Html {
+        if header.is_empty() {
+            return html! {};
+        }
+        let header = serde_json::to_string(header).expect_throw("cannot serialize header");
+        html! {
+            <div class="small">
+                <span class="text-muted">{ title }{ ": " }</span>
+                <code class="text-break-all">{ header }</code>
+            </div>
+        }
+    }
+}
 
 #[derive(Debug)]
 struct TokenInputState {
     raw_token: String,
-    parse_result: Result<Option<ParsedHeader>, ParseError>,
+    detached_payload: String,
+    /// PEM-encoded root certificate to validate the `x5c` chain against.
+    root_certificate: String,
+    /// Validation time for the `x5c` chain; `None` means the current time.
+    evaluated_at: Option<DateTime<Utc>>,
+    /// Whether the token has a detached payload, which should be supplied separately.
+    is_detached: bool,
+    parse_result: Result<Option<TokenHeader>, TokenParseError>,
 }
 
 impl Default for TokenInputState {
     fn default() -> Self {
         Self {
             raw_token: String::new(),
+            detached_payload: String::new(),
+            root_certificate: String::new(),
+            evaluated_at: None,
+            is_detached: false,
             parse_result: Ok(None),
         }
     }
 }
 
 impl TokenInputState {
-    fn new(raw_token: String) -> (Self, Option<UntrustedToken<'static>>) {
-        let token = UntrustedToken::new(&raw_token).map(UntrustedToken::into_owned);
-        let (parse_result, token) = match token {
-            Ok(token) => (Ok(Some(ParsedHeader::new(&token))), Some(token)),
+    fn new(
+        raw_token: String,
+        detached_payload: String,
+        root_certificate: String,
+        evaluated_at: Option<DateTime<Utc>>,
+    ) -> (Self, Option<InputToken>) {
+        let mut is_detached = false;
+        let (parse_result, token) = match InputToken::new(&raw_token) {
+            Ok(InputToken::Detached(token)) if token.is_detached() => {
+                is_detached = true;
+                let token = token.with_detached_payload(detached_payload.as_bytes());
+                let token = InputToken::Detached(token);
+                let header = token.header(&raw_token, &root_certificate, evaluated_at);
+                (Ok(Some(header)), Some(token))
+            }
+            Ok(token) => {
+                let header = token.header(&raw_token, &root_certificate, evaluated_at);
+                (Ok(Some(header)), Some(token))
+            }
             Err(err) => (Err(err), None),
         };
 
         let this = Self {
             raw_token,
+            detached_payload,
+            root_certificate,
+            evaluated_at,
+            is_detached,
             parse_result,
         };
         (this, token)
     }
 }
 
+/// Public key embedded into the token header (`jwk`).
+#[derive(Debug)]
+struct EmbeddedKey {
+    key_type: ExtendedKeyType,
+    thumbprint: String,
+    /// Serialized JWK with only public key parts.
+    jwk: String,
+}
+
+impl EmbeddedKey {
+    fn new(value: &serde_json::Value) -> Result<Self, String> {
+        let jwk: JsonWebKey<'_> =
+            serde_json::from_value(value.clone()).map_err(|err| err.to_string())?;
+        let key = KeyInstance::new(&jwk).map_err(|err| err.to_string())?;
+        if let KeyInstance::Symmetric(_) = &key {
+            return Err("embedded key must be a public key".to_owned());
+        }
+        let public_jwk = key.to_jwk();
+        Ok(Self {
+            key_type: ExtendedKeyType::new(&public_jwk),
+            thumbprint: key.thumbprint(),
+            jwk: serde_json::to_string(&public_jwk).expect_throw("cannot serialize key"),
+        })
+    }
+
+    fn view(&self, link: &Scope<TokenInput>) -> Html {
+        let jwk = self.jwk.clone();
+        html! {
+            <>
+                <div>
+                    { self.key_type.to_string() }
+                    { ", thumbprint (SHA-256): " }
+                    <code>{ &self.thumbprint }</code>
+                </div>
+                <div class="small text-warning-emphasis my-1">
+                    { Icon::Warning.view() }
+                    { " The key is supplied by the token itself, so anyone can sign a token \
+                       with their own key and embed it. A successful verification only proves \
+                       that the token was not modified, not who has issued it." }
+                </div>
+                <button
+                    id="use-embedded-key"
+                    type="button"
+                    class="btn btn-sm btn-outline-warning mb-1"
+                    onclick={link.callback(move |_| TokenInputMessage::UseEmbeddedKey(jwk.clone()))}>
+                    { "Verify with embedded key" }
+                </button>
+            </>
+        }
+    }
+}
+
+/// X.509 certificate chain embedded into the token header (`x5c`).
+#[derive(Debug)]
+struct EmbeddedChain {
+    chain: CertificateChain,
+    /// Serialized JWK of the leaf certificate key.
+    leaf_jwk: Result<String, String>,
+    has_root: bool,
+    validation_result: Result<(), String>,
+}
+
+impl EmbeddedChain {
+    fn new(
+        value: &serde_json::Value,
+        root_certificate: &str,
+        evaluated_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, String> {
+        let chain = CertificateChain::from_header(value).map_err(|err| err.to_string())?;
+        let leaf_jwk = chain
+            .leaf()
+            .public_key()
+            .map(|jwk| serde_json::to_string(jwk).expect_throw("cannot serialize key"))
+            .map_err(ToString::to_string);
+
+        let evaluated_at = evaluated_at.unwrap_or_else(Utc::now);
+        let root_certificate = root_certificate.trim();
+        let has_root = !root_certificate.is_empty();
+        let validation_result = if has_root {
+            Certificate::from_pem(root_certificate)
+                .map_err(|err| format!("invalid root certificate: {err}"))
+                .and_then(|root| {
+                    chain
+                        .validate(Some(&root), evaluated_at)
+                        .map_err(|err| err.to_string())
+                })
+        } else {
+            chain
+                .validate(None, evaluated_at)
+                .map_err(|err| err.to_string())
+        };
+
+        Ok(Self {
+            chain,
+            leaf_jwk,
+            has_root,
+            validation_result,
+        })
+    }
+
+    fn view(&self, link: &Scope<TokenInput>, root_certificate: &str) -> Html {
+        let certificates = self.chain.certificates().iter().enumerate();
+        html! {
+            <>
+                { for certificates.map(|(i, cert)| Self::view_certificate(i, cert)) }
+                { self.view_validation_result() }
+                <label for="x5c-root" class="small text-muted">
+                    { "Trusted root certificate (PEM), used for chain validation" }
+                </label>
+                <textarea
+                    id="x5c-root"
+                    class="form-control mb-1 font-monospace small text-break-all"
+                    placeholder="-----BEGIN CERTIFICATE-----"
+                    rows="3"
+                    autocomplete="off"
+                    spellcheck="false"
+                    value={root_certificate.to_owned()}
+                    oninput={link.callback(|evt| TokenInputMessage::root_certificate_set(&evt))} >
+                    { root_certificate }
+                </textarea>
+                { match &self.leaf_jwk {
+                    Ok(jwk) => {
+                        let jwk = jwk.clone();
+                        html! {
+                            <button
+                                id="use-x5c-key"
+                                type="button"
+                                class="btn btn-sm btn-outline-warning mb-1"
+                                onclick={link.callback(move |_| {
+                                    TokenInputMessage::UseEmbeddedKey(jwk.clone())
+                                })}>
+                                { "Verify with leaf certificate key" }
+                            </button>
+                        }
+                    }
+                    Err(err) => html! {
+                        <div class="small text-danger">
+                            { "Leaf certificate key cannot be used: " }{ err }
+                        </div>
+                    },
+                }}
+            </>
+        }
+    }
+
+    fn view_certificate(index: usize, certificate: &Certificate) -> Html {
+        let title = if index == 0 {
+            "Leaf certificate".to_owned()
+        } else {
+            format!("Certificate #{}", index + 1)
+        };
+        let key_type = match certificate.public_key() {
+            Ok(jwk) => ExtendedKeyType::new(jwk).to_string(),
+            Err(err) => format!("unsupported ({err})"),
+        };
+        html! {
+            <div class="x5c-certificate mb-2">
+                <div><strong>{ title }</strong></div>
+                <div class="small">
+                    <span class="text-muted">{ "Subject: " }</span>
+                    <code class="text-break-all">{ certificate.subject() }</code>
+                </div>
+                <div class="small">
+                    <span class="text-muted">{ "Issuer: " }</span>
+                    <code class="text-break-all">{ certificate.issuer() }</code>
+                </div>
+                <div class="small">
+                    <span class="text-muted">{ "Valid: " }</span>
+                    { certificate.not_before().to_string() }
+                    { " – " }
+                    { certificate.not_after().to_string() }
+                </div>
+                <div class="small">
+                    <span class="text-muted">{ "Key: " }</span>
+                    { key_type }
+                </div>
+            </div>
+        }
+    }
+
+    fn view_validation_result(&self) -> Html {
+        match &self.validation_result {
+            Ok(()) if self.has_root => html! {
+                <div id="x5c-validation" class="small text-success mb-1">
+                    { "The chain is valid and is issued by the provided root certificate." }
+                </div>
+            },
+            Ok(()) => html! {
+                <div id="x5c-validation" class="small text-warning-emphasis mb-1">
+                    { Icon::Warning.view() }
+                    { " The chain is internally consistent, but it is not anchored to a trusted \
+                       root certificate. Provide the root certificate below to validate it." }
+                </div>
+            },
+            Err(err) => html! {
+                <div id="x5c-validation" class="small text-danger mb-1">
+                    { "The chain is invalid: " }{ err }
+                </div>
+            },
+        }
+    }
+}
+
+/// Header of a token in the compact serialization.
 #[derive(Debug)]
 struct ParsedHeader {
-    algorithm: String,
-    header: Header,
+    is_encrypted: bool,
+    fields: serde_json::Map<String, serde_json::Value>,
+    embedded_key: Option<Result<EmbeddedKey, String>>,
+    certificate_chain: Option<Result<EmbeddedChain, String>>,
+    root_certificate: String,
 }
 
 impl ParsedHeader {
@@ -53,46 +493,250 @@ impl ParsedHeader {
         link: Some("https://datatracker.ietf.org/doc/html/rfc7515#section-4.1.1"),
     };
 
-    fn new(token: &UntrustedToken<'_>) -> Self {
+    const JWE_ALG_FIELD: Field = Field {
+        name: "Algorithm",
+        description: "Key management algorithm used to determine the content encryption key. \
+            <code>dir</code> means that the content is encrypted directly with a shared \
+            symmetric key.",
+        link: Some("https://www.rfc-editor.org/rfc/rfc7516.html#section-4.1.1"),
+    };
+
+    /// Standard fields with string values displayed as plain text rather than code.
+    const TEXT_FIELDS: &'static [&'static str] = &["typ", "cty", "enc", "zip"];
+
+    fn new(
+        raw_token: &str,
+        is_encrypted: bool,
+        root_certificate: &str,
+        evaluated_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let encoded_header = raw_token.trim().split('.').next().unwrap_or_default();
+        // The header was successfully parsed when parsing the token, so errors are not expected.
+        let fields: serde_json::Map<_, _> = Base64UrlUnpadded::decode_vec(encoded_header)
+            .ok()
+            .and_then(|header| serde_json::from_slice(&header).ok())
+            .unwrap_or_default();
+        let (embedded_key, certificate_chain) = if is_encrypted {
+            (None, None)
+        } else {
+            let embedded_key = fields.get("jwk").map(EmbeddedKey::new);
+            let certificate_chain = fields
+                .get("x5c")
+                .map(|value| EmbeddedChain::new(value, root_certificate, evaluated_at));
+            (embedded_key, certificate_chain)
+        };
         Self {
-            algorithm: token.algorithm().to_owned(),
-            header: token.header().clone(),
+            is_encrypted,
+            fields,
+            embedded_key,
+            certificate_chain,
+            root_certificate: root_certificate.to_owned(),
         }
     }
 
-    fn view(&self) -> Html {
+    fn view(&self, link: &Scope<TokenInput>) -> Html {
         html! {
             <>
-                { Self::ALG_FIELD.with_value(&self.algorithm).view_aux() }
-                { if let Some(key_set_url) = &self.header.key_set_url {
-                    StandardHeader::by_name("jku").with_code_value(key_set_url).view_aux()
-                } else {
-                    html!{}
-                }}
-                { if let Some(key_id) = &self.header.key_id {
-                    StandardHeader::by_name("kid").with_code_value(key_id).view_aux()
-                } else {
-                    html!{}
-                }}
-                { if let Some(token_type) = &self.header.token_type {
-                    StandardHeader::by_name("typ").with_value(token_type).view_aux()
-                } else {
-                    html!{}
-                }}
-                { if let Some(cert_url) = &self.header.certificate_url {
-                    StandardHeader::by_name("x5u").with_code_value(cert_url).view_aux()
-                } else {
-                    html!{}
-                }}
-                { if let Some(Thumbprint::Bytes(thumb)) = &self.header.certificate_thumbprint {
-                    let thumb = Base64UrlUnpadded::encode_string(thumb);
-                    StandardHeader::by_name("x5t#S256").with_code_value(&thumb).view_aux()
+                { Self::view_nav() }
+                <div class="tab-content">
+                    <div
+                        class="tab-pane fade show active"
+                        id="decoded-header"
+                        role="tabpanel"
+                        aria-labelledby="decoded-header-tab">
+
+                        { self.view_decoded(link) }
+                    </div>
+                    <div
+                        class="tab-pane fade"
+                        id="raw-header"
+                        role="tabpanel"
+                        aria-labelledby="raw-header-tab">
+
+                        { self.view_raw() }
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    fn view_nav() -> Html {
+        html! {
+            <nav class="nav nav-tabs mb-2">
+                <button
+                    class="nav-link disabled ps-0 text-reset"
+                    type="button">
+                    <strong>{ "Header: " }</strong>
+                </button>
+                <button
+                    class="nav-link active"
+                    id="decoded-header-tab"
+                    data-bs-toggle="tab"
+                    data-bs-target="#decoded-header"
+                    type="button"
+                    role="tab"
+                    aria-controls="home"
+                    aria-selected="true">{ "Decoded" }</button>
+                <button
+                    class="nav-link"
+                    id="raw-header-tab"
+                    data-bs-toggle="tab"
+                    data-bs-target="#raw-header"
+                    type="button"
+                    role="tab"
+                    aria-controls="home"
+                    aria-selected="false">{ "Raw" }</button>
+            </nav>
+        }
+    }
+
+    fn view_decoded(&self, link: &Scope<TokenInput>) -> Html {
+        let alg_field = if self.is_encrypted {
+            Self::JWE_ALG_FIELD
+        } else {
+            Self::ALG_FIELD
+        };
+        let alg = self.fields.get("alg").and_then(serde_json::Value::as_str);
+
+        // Standard fields are displayed first, followed by non-standard ones.
+        let (standard_fields, custom_fields): (Vec<_>, Vec<_>) = self
+            .fields
+            .iter()
+            .filter(|(name, _)| *name != "alg")
+            .partition(|(name, _)| StandardHeader::get(name).is_some());
+        let standard_fields = standard_fields.into_iter().map(|(name, value)| {
+            let value = match name.as_str() {
+                "jwk" => match &self.embedded_key {
+                    Some(Ok(key)) => key.view(link),
+                    Some(Err(err)) => Self::view_invalid_value(name, value, "Invalid key: ", err),
+                    None => Self::view_value(name, value),
+                },
+                "x5c" => match &self.certificate_chain {
+                    Some(Ok(chain)) => chain.view(link, &self.root_certificate),
+                    Some(Err(err)) => {
+                        Self::view_invalid_value(name, value, "Invalid certificate chain: ", err)
+                    }
+                    None => Self::view_value(name, value),
+                },
+                "x5t" | "x5t#S256" => html! {
+                    <>
+                        { Self::view_value(name, value) }
+                        { self.view_thumbprint_check(name, value) }
+                    </>
+                },
+                _ => Self::view_value(name, value),
+            };
+            StandardHeader::by_name(name)
+                .with_html_value(value)
+                .view_aux()
+        });
+        let custom_fields = custom_fields
+            .into_iter()
+            .map(|(name, value)| Self::view_custom_field(name, value));
+
+        html! {
+            <>
+                { if let Some(alg) = alg {
+                    alg_field.with_value(&alg).view_aux()
                 } else {
                     html!{}
                 }}
+                { for standard_fields }
+                { for custom_fields }
+            </>
+        }
+    }
+
+    fn view_invalid_value(
+        name: &str,
+        value: &serde_json::Value,
+        err_prefix: &str,
+        err: &str,
+    ) -> Html {
+        html! {
+            <>
+                { Self::view_value(name, value) }
+                <div class="small text-danger">{ err_prefix }{ err }</div>
             </>
         }
     }
+
+    /// Checks the `x5t` or `x5t#S256` header against the leaf certificate from the `x5c` chain.
+    fn view_thumbprint_check(&self, name: &str, value: &serde_json::Value) -> Html {
+        let Some(Ok(embedded)) = &self.certificate_chain else {
+            return html! {};
+        };
+        let thumbprints = embedded.chain.leaf().thumbprints();
+        let expected = if name == "x5t" {
+            thumbprints.sha1
+        } else {
+            thumbprints.sha256
+        };
+        let actual = value.as_str().map(|value| value.trim_end_matches('='));
+        if actual == Some(expected.as_str()) {
+            html! {
+                <div class="small text-success">
+                    { "Matches the leaf certificate from the " }<code>{ "x5c" }</code>{ " header." }
+                </div>
+            }
+        } else {
+            html! {
+                <div class="small text-danger">
+                    { "Does not match the leaf certificate from the " }<code>{ "x5c" }</code>
+                    { " header; its thumbprint is " }<code>{ expected }</code>
+                </div>
+            }
+        }
+    }
+
+    fn view_value(name: &str, value: &serde_json::Value) -> Html {
+        match value {
+            serde_json::Value::String(value) if Self::TEXT_FIELDS.contains(&name) => {
+                html! { value }
+            }
+            serde_json::Value::String(value) => html! { <code>{ value }</code> },
+            serde_json::Value::Array(items) if items.iter().all(serde_json::Value::is_string) => {
+                let items = items.iter().enumerate().map(|(i, item)| {
+                    let separator = if i == 0 { "" } else { ", " };
+                    let item = item.as_str().unwrap_or_default();
+                    html! { <>{ separator }<code class="text-break-all">{ item }</code></> }
+                });
+                html! { <>{ for items }</> }
+            }
+            _ => {
+                let value = serde_json::to_string(value).expect_throw("cannot serialize value");
+                html! { <code class="text-break-all">{ value }</code> }
+            }
+        }
+    }
+
+    fn view_custom_field(name: &str, value: &serde_json::Value) -> Html {
+        let value = serde_json::to_string(value).expect_throw("cannot serialize value");
+        view_data_row(
+            html! {
+                <div class="ps-md-2">
+                    <label class="text-decoration--md-underline">{ name }</label>
+                    { " " }
+                    <span
+                        class="badge bg-secondary fw-normal"
+                        title="This header field is not described by any standard known to the site">
+                        { "non-standard" }
+                    </span>
+                </div>
+            },
+            html! { <div><code class="text-break-all">{ value }</code></div> },
+        )
+    }
+
+    fn view_raw(&self) -> Html {
+        let serialized =
+            serde_json::to_string_pretty(&self.fields).expect_throw("cannot serialize header");
+        view_code_snippet(
+            "raw-header-content",
+            "Copy raw header to clipboard",
+            &serialized,
+        )
+    }
 }
 
 /// Properties for the `TokenInput` component.
@@ -101,9 +745,16 @@ pub struct TokenInputProperties {
     #[prop_or_default]
     pub component_ref: ComponentRef<TokenInput>,
     #[prop_or_default]
-    pub onchange: Callback<Option<UntrustedToken<'static>>>,
+    pub onchange: Callback<Option<InputToken>>,
+    /// Invoked with the serialized JWK when the user opts to verify the token
+    /// with the key embedded into its header.
+    #[prop_or_default]
+    pub onembeddedkey: Callback<String>,
     #[prop_or_default]
     pub save: bool,
+    /// Validation time for the `x5c` certificate chain; `None` means the current time.
+    #[prop_or_default]
+    pub evaluated_at: Option<DateTime<Utc>>,
 }
 
 /// Token input + corresponding diagnostic information.
@@ -116,6 +767,10 @@ pub struct TokenInput {
 #[derive(Debug)]
 pub enum TokenInputMessage {
     SetToken(String),
+    SetDetachedPayload(String),
+    SetRootCertificate(String),
+    /// Requests to use the key embedded into the token header for verification.
+    UseEmbeddedKey(String),
 }
 
 impl TokenInputMessage {
@@ -126,6 +781,26 @@ impl TokenInputMessage {
             .expect_throw("unexpected target for token set event");
         Self::SetToken(target.value())
     }
+
+    fn detached_payload_set(event: &InputEvent) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for detached payload set event");
+        let target = target
+            .dyn_into::<HtmlTextAreaElement>()
+            .expect_throw("unexpected target for detached payload set event");
+        Self::SetDetachedPayload(target.value())
+    }
+
+    fn root_certificate_set(event: &InputEvent) -> Self {
+        let target = event
+            .target()
+            .expect_throw("no target for root certificate set event");
+        let target = target
+            .dyn_into::<HtmlTextAreaElement>()
+            .expect_throw("unexpected target for root certificate set event");
+        Self::SetRootCertificate(target.value())
+    }
 }
 
 impl Component for TokenInput {
@@ -148,14 +823,31 @@ impl Component for TokenInput {
     }
 
     fn update(&mut self, ctx: &Context<Self>, message: Self::Message) -> bool {
-        match message {
+        let evaluated_at = ctx.props().evaluated_at;
+        let (new_state, maybe_token) = match message {
             TokenInputMessage::SetToken(token) => {
                 self.state_manager.save(&token);
-                let (new_state, maybe_token) = TokenInputState::new(token);
-                self.state = new_state;
-                ctx.props().onchange.emit(maybe_token);
+                let payload = mem::take(&mut self.state.detached_payload);
+                let root = mem::take(&mut self.state.root_certificate);
+                TokenInputState::new(token, payload, root, evaluated_at)
             }
-        }
+            TokenInputMessage::SetDetachedPayload(payload) => {
+                let token = mem::take(&mut self.state.raw_token);
+                let root = mem::take(&mut self.state.root_certificate);
+                TokenInputState::new(token, payload, root, evaluated_at)
+            }
+            TokenInputMessage::SetRootCertificate(root) => {
+                let token = mem::take(&mut self.state.raw_token);
+                let payload = mem::take(&mut self.state.detached_payload);
+                TokenInputState::new(token, payload, root, evaluated_at)
+            }
+            TokenInputMessage::UseEmbeddedKey(jwk) => {
+                ctx.props().onembeddedkey.emit(jwk);
+                return false;
+            }
+        };
+        self.state = new_state;
+        ctx.props().onchange.emit(maybe_token);
         true
     }
 
@@ -163,7 +855,18 @@ impl Component for TokenInput {
         ctx.props().component_ref.link_with(ctx.link().clone());
         self.state_manager.set_save_flag(ctx.props().save);
         self.state_manager.save(&self.state.raw_token);
-        false
+
+        let evaluated_at = ctx.props().evaluated_at;
+        if evaluated_at == self.state.evaluated_at {
+            return false;
+        }
+        // Only the header view depends on the validation time; the token itself is unchanged,
+        // so it is not re-emitted.
+        let token = mem::take(&mut self.state.raw_token);
+        let payload = mem::take(&mut self.state.detached_payload);
+        let root = mem::take(&mut self.state.root_certificate);
+        (self.state, _) = TokenInputState::new(token, payload, root, evaluated_at);
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -211,8 +914,13 @@ impl Component for TokenInput {
         html! {
             <>
                 { row }
+                { if self.state.is_detached {
+                    self.view_detached_payload(ctx)
+                } else {
+                    html!{}
+                }}
                 { if let Ok(Some(header)) = parse_res {
-                    header.view()
+                    header.view(link)
                 } else {
                     html!{}
                 }}
@@ -224,7 +932,40 @@ impl Component for TokenInput {
 impl TokenInput {
     const STORAGE_KEY: &'static str = "jwt__rawToken";
 
-    fn view_parse_err(err: &ParseError) -> Html {
+    fn view_detached_payload(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        view_wide_data_row(
+            html! {
+                <label for="detached-payload">
+                    <strong>{ "Detached payload" }</strong>
+                </label>
+            },
+            html! {
+                <>
+                    <textarea
+                        id="detached-payload"
+                        class="form-control mb-1 font-monospace small text-break-all"
+                        placeholder="Payload transmitted separately from the token"
+                        autocomplete="off"
+                        spellcheck="false"
+                        value={self.state.detached_payload.clone()}
+                        oninput={link.callback(|evt| {
+                            TokenInputMessage::detached_payload_set(&evt)
+                        })} >
+                        { &self.state.detached_payload }
+                    </textarea>
+                    <p class="text-muted small mb-1">
+                        { "The token payload is detached. Paste the payload as is; it is encoded \
+                           according to the " }
+                        <code>{ "b64" }</code>
+                        { " header when building the signing input." }
+                    </p>
+                </>
+            },
+        )
+    }
+
+    fn view_parse_err(err: &TokenParseError) -> Html {
         html! {
             <p class="invalid-feedback mb-1">{ "Error deserializing token: " }{ err }</p>
         }
@@ -0,0 +1,327 @@
+//! Form for creating signed tokens from a private JSON web key.
+
+use jwt_compact::{jwk::JsonWebKey, Claims};
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
+use web_sys::{HtmlTextAreaElement, InputEvent};
+use yew::{classes, html, Callback, Component, Context, Html, Properties};
+
+use super::common::{view_code_snippet, view_wide_data_row, Alert, ComponentRef};
+use crate::{
+    fields::{Field, StandardHeader},
+    keys::{GenericClaims, GenericHeader, SigningError, SigningKeyInstance},
+};
+
+/// Successfully created token.
+#[derive(Debug)]
+struct SignedToken {
+    algorithm: String,
+    key_id: Option<String>,
+    token: String,
+}
+
+impl SignedToken {
+    const ALG_FIELD: Field = Field {
+        name: "Algorithm",
+        description: "Algorithm used to sign the token. Unless specified in the <code>alg</code> \
+            header field, the algorithm is chosen based on the key type.",
+        link: Some("https://datatracker.ietf.org/doc/html/rfc7515#section-4.1.1"),
+    };
+
+    fn view(&self) -> Html {
+        html! {
+            <>
+                { Self::ALG_FIELD.with_value(&self.algorithm).view_aux() }
+                { if let Some(key_id) = &self.key_id {
+                    StandardHeader::by_name("kid").with_code_value(key_id).view_aux()
+                } else {
+                    html!{}
+                }}
+                <div class="mt-3">
+                    { view_code_snippet("created-token", "Copy token to clipboard", &self.token) }
+                </div>
+            </>
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenSignerState {
+    raw_key: String,
+    raw_header: String,
+    raw_claims: String,
+    key_error: Option<String>,
+    header_error: Option<String>,
+    claims_error: Option<String>,
+    signing_result: Option<Result<SignedToken, SigningError>>,
+}
+
+impl Default for TokenSignerState {
+    fn default() -> Self {
+        Self {
+            raw_key: String::new(),
+            raw_header: "{ \"typ\": \"JWT\" }".to_owned(),
+            raw_claims: String::new(),
+            key_error: None,
+            header_error: None,
+            claims_error: None,
+            signing_result: None,
+        }
+    }
+}
+
+impl TokenSignerState {
+    fn update(&mut self) {
+        let key = Self::parse_key(&self.raw_key);
+        let header = Self::parse_header(&self.raw_header);
+        let claims = Self::parse_claims(&self.raw_claims);
+        self.key_error = key.as_ref().err().cloned();
+        self.header_error = header.as_ref().err().cloned();
+        self.claims_error = claims.as_ref().err().cloned();
+
+        self.signing_result = match (key, header, claims) {
+            (Ok(Some(key)), Ok((algorithm, mut header)), Ok(claims)) => {
+                let algorithm = algorithm.unwrap_or_else(|| key.default_algorithm().to_owned());
+                if header.key_id.is_none() {
+                    header.key_id = Some(key.to_verifying_key().thumbprint());
+                }
+                let result = key.sign_token(&algorithm, &header, &claims);
+                Some(result.map(|token| SignedToken {
+                    algorithm,
+                    key_id: header.key_id,
+                    token,
+                }))
+            }
+            _ => None,
+        };
+    }
+
+    fn parse_key(raw_key: &str) -> Result<Option<SigningKeyInstance>, String> {
+        if raw_key.trim().is_empty() {
+            return Ok(None);
+        }
+        let jwk: JsonWebKey<'_> = serde_json::from_str(raw_key).map_err(|err| err.to_string())?;
+        if !jwk.is_signing_key() {
+            return Err("key does not contain private parts".to_owned());
+        }
+        SigningKeyInstance::new(&jwk)
+            .map(Some)
+            .map_err(|err| err.to_string())
+    }
+
+    fn parse_json_object(raw: &str) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+        if raw.trim().is_empty() {
+            Ok(serde_json::Map::new())
+        } else {
+            serde_json::from_str(raw).map_err(|err| err.to_string())
+        }
+    }
+
+    /// Returns the algorithm specified in the header (if any) and the remaining header fields.
+    fn parse_header(raw_header: &str) -> Result<(Option<String>, GenericHeader), String> {
+        let mut fields = Self::parse_json_object(raw_header)?;
+        let algorithm = match fields.remove("alg") {
+            None => None,
+            Some(serde_json::Value::String(alg)) => Some(alg),
+            Some(_) => return Err("`alg` header field must be a string".to_owned()),
+        };
+        let header = serde_json::from_value(fields.into()).map_err(|err| err.to_string())?;
+        Ok((algorithm, header))
+    }
+
+    fn parse_claims(raw_claims: &str) -> Result<GenericClaims, String> {
+        // Claims are not deserialized into `GenericClaims` directly in order to preserve
+        // time-related claims verbatim.
+        let claims = Self::parse_json_object(raw_claims)?;
+        Ok(Claims::new(claims.into()))
+    }
+}
+
+#[derive(Debug)]
+pub enum TokenSignerMessage {
+    SetKey(String),
+    SetHeader(String),
+    SetClaims(String),
+}
+
+impl TokenSignerMessage {
+    fn textarea_value(event: &InputEvent) -> String {
+        let target = event.target().expect_throw("no target for input event");
+        let target = target
+            .dyn_into::<HtmlTextAreaElement>()
+            .expect_throw("unexpected target for input event");
+        target.value()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Properties)]
+pub struct TokenSignerProperties {
+    #[prop_or_default]
+    pub component_ref: ComponentRef<TokenSigner>,
+}
+
+/// Form for creating tokens. Unlike the verification inputs, the inputs of this form
+/// are never saved in the local storage since they contain a private key.
+#[derive(Debug, Default)]
+pub struct TokenSigner {
+    state: TokenSignerState,
+}
+
+impl Component for TokenSigner {
+    type Message = TokenSignerMessage;
+    type Properties = TokenSignerProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.props().component_ref.link_with(ctx.link().clone());
+        Self::default()
+    }
+
+    fn update(&mut self, _: &Context<Self>, message: Self::Message) -> bool {
+        match message {
+            TokenSignerMessage::SetKey(key) => self.state.raw_key = key,
+            TokenSignerMessage::SetHeader(header) => self.state.raw_header = header,
+            TokenSignerMessage::SetClaims(claims) => self.state.raw_claims = claims,
+        }
+        self.state.update();
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        ctx.props().component_ref.link_with(ctx.link().clone());
+        false
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let link = ctx.link();
+        let state = &self.state;
+        html! {
+            <>
+                <form class="mb-4">
+                    <div class="mb-3">
+                        { Self::view_input(
+                            "signing-key",
+                            "Signing key",
+                            "Private JSON web key",
+                            &state.raw_key,
+                            state.key_error.as_deref(),
+                            link.callback(|evt| {
+                                TokenSignerMessage::SetKey(TokenSignerMessage::textarea_value(&evt))
+                            }),
+                            Self::view_key_hint(),
+                        ) }
+                    </div>
+                    <div class="mb-3">
+                        { Self::view_input(
+                            "token-header",
+                            "Header",
+                            "Header fields as a JSON object",
+                            &state.raw_header,
+                            state.header_error.as_deref(),
+                            link.callback(|evt| {
+                                TokenSignerMessage::SetHeader(
+                                    TokenSignerMessage::textarea_value(&evt),
+                                )
+                            }),
+                            Self::view_header_hint(),
+                        ) }
+                    </div>
+                    { Self::view_input(
+                        "token-claims",
+                        "Claims",
+                        "Claims as a JSON object",
+                        &state.raw_claims,
+                        state.claims_error.as_deref(),
+                        link.callback(|evt| {
+                            TokenSignerMessage::SetClaims(TokenSignerMessage::textarea_value(&evt))
+                        }),
+                        html! {},
+                    ) }
+                </form>
+
+                { match &state.signing_result {
+                    Some(Ok(token)) => token.view(),
+                    Some(Err(err)) => Alert::Danger.view(
+                        "Error creating token",
+                        html! { <p class="card-text">{ err.to_string() }</p> },
+                    ),
+                    None => html! {},
+                }}
+            </>
+        }
+    }
+}
+
+impl TokenSigner {
+    #[allow(clippy::too_many_arguments)] // more readable than introducing a struct
+    fn view_input(
+        id: &'static str,
+        label: &'static str,
+        placeholder: &'static str,
+        value: &str,
+        err: Option<&str>,
+        oninput: Callback<InputEvent>,
+        hint: Html,
+    ) -> Html {
+        let mut control_classes = classes![
+            "form-control",
+            "mb-1",
+            "font-monospace",
+            "small",
+            "text-break-all"
+        ];
+        if err.is_some() {
+            control_classes.push("is-invalid");
+        }
+
+        view_wide_data_row(
+            html! {
+                <label for={id}>
+                    <strong>{ label }</strong>
+                </label>
+            },
+            html! {
+                <>
+                    <textarea
+                        {id}
+                        class={control_classes}
+                        {placeholder}
+                        autocomplete="off"
+                        spellcheck="false"
+                        value={value.to_owned()}
+                        {oninput}>
+                        { value }
+                    </textarea>
+                    { if let Some(err) = err {
+                        html! { <p class="invalid-feedback mb-1">{ err }</p> }
+                    } else {
+                        html!{}
+                    }}
+                    { hint }
+                </>
+            },
+        )
+    }
+
+    fn view_key_hint() -> Html {
+        html! {
+            <div class="form-text">
+                { "A key should be provided in the " }
+                <a href="https://www.rfc-editor.org/rfc/rfc7517.html">{ "JSON Web Key" }</a>
+                { " format and contain private parts (e.g., the " }
+                <code>{ "d" }</code>
+                { " field for elliptic curve and RSA keys). The key is never saved \
+                   in the local browser storage." }
+            </div>
+        }
+    }
+
+    fn view_header_hint() -> Html {
+        html! {
+            <div class="form-text">
+                { "If " }<code>{ "alg" }</code>
+                { " is not specified, it is chosen based on the key type. If " }
+                <code>{ "kid" }</code>
+                { " is not specified, it is set to the SHA-256 key thumbprint." }
+            </div>
+        }
+    }
+}
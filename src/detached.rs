@@ -0,0 +1,216 @@
+//! Signed tokens with a detached (RFC 7515, appendix F) and / or unencoded
+//! (`b64: false`, RFC 7797) payload.
+//!
+//! `jwt-compact` always computes the signing input from the base64url-encoded payload
+//! embedded into the token, so such tokens are parsed separately; their signatures are verified
+//! via [`KeySet::verify_signature()`].
+//!
+//! [`KeySet::verify_signature()`]: crate::keys::KeySet::verify_signature()
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use jwt_compact::{Header, ParseError};
+
+use std::fmt;
+
+use crate::{keys::SignatureInput, nested::is_nested_content_type};
+
+/// Checks whether the provided compact token should be parsed as a [`DetachedToken`]. This is
+/// the case if the token has a detached payload (i.e., an empty payload segment), or uses
+/// `b64` or `crit` headers, which are not supported by `jwt-compact`. Nested tokens
+/// (`cty: JWT`) are excluded; their critical headers are handled separately.
+pub fn is_detached_token(raw: &str) -> bool {
+    let segments: Vec<_> = raw.trim().split('.').collect();
+    let [encoded_header, encoded_payload, _] = segments[..] else {
+        return false;
+    };
+    if encoded_payload.is_empty() {
+        return true;
+    }
+
+    let Ok(header) = Base64UrlUnpadded::decode_vec(encoded_header) else {
+        return false;
+    };
+    let Ok(header) = serde_json::from_slice::<serde_json::Value>(&header) else {
+        return false;
+    };
+    let is_nested = header
+        .get("cty")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(is_nested_content_type);
+    header.get("b64").is_some() || (header.get("crit").is_some() && !is_nested)
+}
+
+/// Errors that can occur when parsing a [`DetachedToken`].
+#[derive(Debug)]
+pub enum DetachedParseError {
+    /// Generic parsing error.
+    Parse(ParseError),
+    /// `b64` header is not listed in `crit`.
+    NonCriticalEncoding,
+}
+
+impl fmt::Display for DetachedParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => fmt::Display::fmt(err, formatter),
+            Self::NonCriticalEncoding => formatter
+                .write_str("`b64` header must be listed in `crit` header (RFC 7797, section 6)"),
+        }
+    }
+}
+
+impl From<ParseError> for DetachedParseError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+/// Signed token with a detached and / or unencoded payload. Also used for tokens with
+/// critical header extensions (`crit`), so that they can be checked before verification.
+#[derive(Debug, Clone)]
+pub struct DetachedToken {
+    algorithm: String,
+    header: Header,
+    critical: Vec<String>,
+    is_payload_encoded: bool,
+    is_detached: bool,
+    encoded_header: String,
+    /// Attached payload, or the detached payload if it is provided.
+    payload: Option<Vec<u8>>,
+    /// Signing input; only set if the payload is available.
+    signed_data: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+impl DetachedToken {
+    /// Parses a token from the compact serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed, or if it has the `b64` header not marked
+    /// as critical.
+    pub fn new(raw: &str) -> Result<Self, DetachedParseError> {
+        let raw = raw.trim();
+        let segments: Vec<_> = raw.split('.').collect();
+        let [encoded_header, payload, encoded_signature] = segments[..] else {
+            return Err(ParseError::InvalidTokenStructure.into());
+        };
+        let decode = |segment: &str| {
+            Base64UrlUnpadded::decode_vec(segment).map_err(|_| ParseError::InvalidBase64Encoding)
+        };
+
+        let raw_header = decode(encoded_header)?;
+        let mut fields: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&raw_header).map_err(ParseError::MalformedHeader)?;
+        let algorithm = fields.remove("alg").unwrap_or_default();
+        let algorithm: String =
+            serde_json::from_value(algorithm).map_err(ParseError::MalformedHeader)?;
+        let is_payload_encoded: Option<bool> = fields
+            .remove("b64")
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(ParseError::MalformedHeader)?;
+        let critical: Vec<String> = fields
+            .remove("crit")
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(ParseError::MalformedHeader)?
+            .unwrap_or_default();
+        if is_payload_encoded.is_some() && !critical.iter().any(|name| name == "b64") {
+            return Err(DetachedParseError::NonCriticalEncoding);
+        }
+        let is_payload_encoded = is_payload_encoded.unwrap_or(true);
+        let header = serde_json::from_value(fields.into()).map_err(ParseError::MalformedHeader)?;
+
+        let is_detached = payload.is_empty();
+        let payload = if is_detached {
+            None
+        } else if is_payload_encoded {
+            Some(decode(payload)?)
+        } else {
+            Some(payload.as_bytes().to_vec())
+        };
+
+        let mut this = Self {
+            algorithm,
+            header,
+            critical,
+            is_payload_encoded,
+            is_detached,
+            encoded_header: encoded_header.to_owned(),
+            payload: None,
+            signed_data: None,
+            signature: decode(encoded_signature)?,
+        };
+        if let Some(payload) = payload {
+            this.set_payload(payload);
+        }
+        Ok(this)
+    }
+
+    /// Sets the payload and builds the signing input as per RFC 7797, section 3.
+    fn set_payload(&mut self, payload: Vec<u8>) {
+        let mut signed_data = format!("{}.", self.encoded_header).into_bytes();
+        if self.is_payload_encoded {
+            signed_data.extend_from_slice(Base64UrlUnpadded::encode_string(&payload).as_bytes());
+        } else {
+            signed_data.extend_from_slice(&payload);
+        }
+        self.signed_data = Some(signed_data);
+        self.payload = Some(payload);
+    }
+
+    /// Sets the detached payload. Has no effect if the payload is attached to the token.
+    #[must_use]
+    pub fn with_detached_payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        if self.is_detached {
+            self.set_payload(payload.into());
+        }
+        self
+    }
+
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns names of critical header fields (`crit`).
+    pub fn critical_fields(&self) -> &[String] {
+        &self.critical
+    }
+
+    /// Checks whether the payload is base64url-encoded in the signing input
+    /// (i.e., the `b64` header is absent or set to `true`).
+    pub fn is_payload_encoded(&self) -> bool {
+        self.is_payload_encoded
+    }
+
+    /// Checks whether the payload is detached from the token.
+    pub fn is_detached(&self) -> bool {
+        self.is_detached
+    }
+
+    /// Returns the payload. Returns `None` if the payload is detached and was not
+    /// [provided](Self::with_detached_payload()). The payload is not authenticated until
+    /// the signature is verified.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+
+    pub fn signature_bytes(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Returns the input for signature verification, or `None` if the payload is not available.
+    pub fn signature_input(&self) -> Option<SignatureInput<'_>> {
+        Some(SignatureInput {
+            algorithm: &self.algorithm,
+            key_id: self.header.key_id.as_deref(),
+            signed_data: self.signed_data.as_deref()?,
+            signature: &self.signature,
+        })
+    }
+}
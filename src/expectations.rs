@@ -0,0 +1,180 @@
+//! Verifier expectations for tokens: expected issuer(s), audience and subject, required claims
+//! and allowed signing algorithms. These mirror checks that services usually perform
+//! on top of signature and time claim validation.
+
+use std::fmt;
+
+use crate::keys::GenericClaims;
+
+/// Field of [`Expectations`] that can be set from a raw string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectationField {
+    Issuers,
+    Audience,
+    Subject,
+    RequiredClaims,
+    Algorithms,
+}
+
+/// Expectations for a verified token. Empty fields impose no restrictions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expectations {
+    /// Accepted issuers (`iss` claim).
+    pub issuers: Vec<String>,
+    /// Audience that must be included into the `aud` claim.
+    pub audience: Option<String>,
+    /// Expected subject (`sub` claim).
+    pub subject: Option<String>,
+    /// Claims that must be present in the token.
+    pub required_claims: Vec<String>,
+    /// Allowed signing algorithms (`alg` header of each signature).
+    pub algorithms: Vec<String>,
+}
+
+impl Expectations {
+    /// Splits a list separated by commas and / or whitespace.
+    fn parse_list(raw: &str) -> Vec<String> {
+        raw.split(|ch: char| ch == ',' || ch.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    fn parse_single(raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        (!raw.is_empty()).then(|| raw.to_owned())
+    }
+
+    /// Sets the specified field from a raw string. Lists are separated by commas
+    /// and / or whitespace.
+    pub fn set(&mut self, field: ExpectationField, raw: &str) {
+        match field {
+            ExpectationField::Issuers => self.issuers = Self::parse_list(raw),
+            ExpectationField::Audience => self.audience = Self::parse_single(raw),
+            ExpectationField::Subject => self.subject = Self::parse_single(raw),
+            ExpectationField::RequiredClaims => self.required_claims = Self::parse_list(raw),
+            ExpectationField::Algorithms => self.algorithms = Self::parse_list(raw),
+        }
+    }
+
+    /// Returns the raw string representation of the specified field.
+    pub fn get(&self, field: ExpectationField) -> String {
+        match field {
+            ExpectationField::Issuers => self.issuers.join(", "),
+            ExpectationField::Audience => self.audience.clone().unwrap_or_default(),
+            ExpectationField::Subject => self.subject.clone().unwrap_or_default(),
+            ExpectationField::RequiredClaims => self.required_claims.join(", "),
+            ExpectationField::Algorithms => self.algorithms.join(", "),
+        }
+    }
+
+    /// Checks signing algorithms used by the token.
+    pub fn check_algorithms<'a>(
+        &self,
+        algorithms: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<ExpectationError> {
+        if self.algorithms.is_empty() {
+            return vec![];
+        }
+        algorithms
+            .into_iter()
+            .filter(|&alg| !self.algorithms.iter().any(|allowed| allowed == alg))
+            .map(|alg| ExpectationError::AlgorithmNotAllowed(alg.to_owned()))
+            .collect()
+    }
+
+    /// Checks token claims. Returns all failed expectations.
+    pub fn check_claims(&self, claims: &GenericClaims) -> Vec<ExpectationError> {
+        let mut errors = vec![];
+        let custom = claims.custom.as_object();
+        let get_claim = |name: &str| custom.and_then(|custom| custom.get(name));
+        let get_str_claim = |name: &str| get_claim(name).and_then(serde_json::Value::as_str);
+
+        if !self.issuers.is_empty() {
+            let issuer = get_str_claim("iss");
+            if !issuer.is_some_and(|issuer| self.issuers.iter().any(|iss| iss == issuer)) {
+                errors.push(ExpectationError::Issuer(issuer.map(str::to_owned)));
+            }
+        }
+
+        if let Some(expected) = &self.audience {
+            // As per RFC 7519, `aud` may be a single string or an array of strings.
+            let audience: Vec<_> = match get_claim("aud") {
+                Some(serde_json::Value::String(aud)) => vec![aud.as_str()],
+                Some(serde_json::Value::Array(aud)) => {
+                    aud.iter().filter_map(serde_json::Value::as_str).collect()
+                }
+                _ => vec![],
+            };
+            if !audience.contains(&expected.as_str()) {
+                let audience = audience.into_iter().map(str::to_owned).collect();
+                errors.push(ExpectationError::Audience(audience));
+            }
+        }
+
+        if let Some(expected) = &self.subject {
+            let subject = get_str_claim("sub");
+            if subject != Some(expected.as_str()) {
+                errors.push(ExpectationError::Subject(subject.map(str::to_owned)));
+            }
+        }
+
+        for name in &self.required_claims {
+            let is_present = match name.as_str() {
+                "exp" => claims.expiration.is_some(),
+                "nbf" => claims.not_before.is_some(),
+                "iat" => claims.issued_at.is_some(),
+                _ => get_claim(name).is_some(),
+            };
+            if !is_present {
+                errors.push(ExpectationError::MissingClaim(name.clone()));
+            }
+        }
+        errors
+    }
+}
+
+/// Failed [`Expectations`] check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectationError {
+    /// Issuer is missing or is not among the accepted ones.
+    Issuer(Option<String>),
+    /// Audience does not include the expected one.
+    Audience(Vec<String>),
+    /// Subject is missing or differs from the expected one.
+    Subject(Option<String>),
+    /// Required claim is missing.
+    MissingClaim(String),
+    /// Token is signed with an algorithm that is not allowed.
+    AlgorithmNotAllowed(String),
+}
+
+impl fmt::Display for ExpectationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Issuer(None) => formatter.write_str("issuer (`iss`) is missing"),
+            Self::Issuer(Some(issuer)) => {
+                write!(formatter, "issuer `{issuer}` is not among accepted ones")
+            }
+            Self::Audience(audience) if audience.is_empty() => {
+                formatter.write_str("audience (`aud`) is missing")
+            }
+            Self::Audience(audience) => write!(
+                formatter,
+                "audience `{}` does not include the expected one",
+                audience.join(", ")
+            ),
+            Self::Subject(None) => formatter.write_str("subject (`sub`) is missing"),
+            Self::Subject(Some(subject)) => {
+                write!(
+                    formatter,
+                    "subject `{subject}` differs from the expected one"
+                )
+            }
+            Self::MissingClaim(name) => write!(formatter, "required claim `{name}` is missing"),
+            Self::AlgorithmNotAllowed(alg) => {
+                write!(formatter, "signing algorithm `{alg}` is not allowed")
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Standard claims and token headers.
 
+use chrono::NaiveDate;
 use once_cell::sync::Lazy;
 use wasm_bindgen::UnwrapThrowExt;
 use yew::{html, Html};
@@ -13,22 +14,25 @@ pub struct Field {
     pub link: Option<&'static str>,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct FieldWithValue {
     pub field: Field,
     pub value: Html,
+    /// Human-readable description of the expected value format.
+    pub format: Option<&'static str>,
 }
 
 impl Field {
     pub fn with_value(self, value: &dyn fmt::Display) -> FieldWithValue {
-        FieldWithValue {
-            field: self,
-            value: html! { value },
-        }
+        self.with_html_value(html! { value })
     }
 
     pub fn with_html_value(self, value: Html) -> FieldWithValue {
-        FieldWithValue { field: self, value }
+        FieldWithValue {
+            field: self,
+            value,
+            format: None,
+        }
     }
 
     pub fn with_code_value(self, value: &dyn fmt::Display) -> FieldWithValue {
@@ -40,16 +44,196 @@ impl Field {
 pub struct StandardClaim {
     pub field: Field,
     pub category: &'static str,
+    /// Human-readable description of the claim value format.
+    pub format: Option<&'static str>,
+    pub format_rule: Option<FormatRule>,
+}
+
+/// Machine-checkable format of a claim value, as specified by `format_rule` in `fields.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatRule {
+    String,
+    Uri,
+    Bool,
+    /// Phone number in the E.164 format, potentially with separators between digits.
+    Phone,
+    Email,
+    /// BCP 47 language tag; both `-` and `_` separators are allowed.
+    Locale,
+    /// IANA time zone identifier. Only the identifier shape is checked.
+    Zoneinfo,
+    /// `YYYY-MM-DD` date; a single `YYYY` year is allowed as well.
+    Date,
+    /// List of printable ASCII tokens separated by single spaces, such as OAuth 2.0 scopes.
+    SpaceList,
+    /// Integer Unix timestamp.
+    Timestamp,
+    StringOrArray,
+    StringArray,
+    Object,
+}
+
+impl FormatRule {
+    fn description(self) -> &'static str {
+        match self {
+            Self::String => "a string",
+            Self::Uri => "a URI",
+            Self::Bool => "a boolean",
+            Self::Phone => "an E.164 phone number",
+            Self::Email => "an email address",
+            Self::Locale => "a BCP 47 language tag",
+            Self::Zoneinfo => "an IANA time zone",
+            Self::Date => "a YYYY-MM-DD date",
+            Self::SpaceList => "a space-separated list",
+            Self::Timestamp => "a Unix timestamp",
+            Self::StringOrArray => "a string or an array of strings",
+            Self::StringArray => "an array of strings",
+            Self::Object => "an object",
+        }
+    }
+
+    /// Checks whether the value matches this format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value does not match.
+    pub fn check(self, value: &serde_json::Value) -> Result<(), FormatMismatch> {
+        use serde_json::Value;
+
+        let is_match = match (self, value) {
+            (Self::String | Self::StringOrArray, Value::String(_))
+            | (Self::Bool, Value::Bool(_))
+            | (Self::Object, Value::Object(_)) => true,
+            (Self::Timestamp, Value::Number(number)) => number.is_i64() || number.is_u64(),
+            (Self::StringOrArray | Self::StringArray, Value::Array(items)) => {
+                items.iter().all(Value::is_string)
+            }
+            (Self::Uri, Value::String(s)) => is_uri(s),
+            (Self::Phone, Value::String(s)) => is_phone_number(s),
+            (Self::Email, Value::String(s)) => is_email(s),
+            (Self::Locale, Value::String(s)) => is_locale(s),
+            (Self::Zoneinfo, Value::String(s)) => is_zoneinfo(s),
+            (Self::Date, Value::String(s)) => is_date(s),
+            (Self::SpaceList, Value::String(s)) => is_space_list(s),
+            _ => false,
+        };
+        if is_match {
+            Ok(())
+        } else {
+            Err(FormatMismatch { rule: self })
+        }
+    }
+}
+
+/// Error returned by [`FormatRule::check()`].
+#[derive(Debug, Clone, Copy)]
+pub struct FormatMismatch {
+    pub rule: FormatRule,
+}
+
+impl fmt::Display for FormatMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "expected {}", self.rule.description())
+    }
+}
+
+/// Checks an absolute URI: a scheme (RFC 3986, section 3.1) followed by a non-empty remainder.
+fn is_uri(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once(':') else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    let is_scheme_valid = scheme_chars
+        .next()
+        .is_some_and(|ch| ch.is_ascii_alphabetic())
+        && scheme_chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'));
+    is_scheme_valid && !rest.is_empty() && !s.chars().any(char::is_whitespace)
+}
+
+fn is_phone_number(s: &str) -> bool {
+    let Some(rest) = s.strip_prefix('+') else {
+        return false;
+    };
+    let is_charset_valid = rest
+        .chars()
+        .all(|ch| ch.is_ascii_digit() || matches!(ch, ' ' | '-' | '(' | ')' | '.'));
+    let digit_count = rest.chars().filter(char::is_ascii_digit).count();
+    is_charset_valid && (1..=15).contains(&digit_count)
+}
+
+fn is_email(s: &str) -> bool {
+    let Some((local_part, domain)) = s.rsplit_once('@') else {
+        return false;
+    };
+    let is_local_part_valid = !local_part.is_empty()
+        && !local_part.contains('@')
+        && !local_part
+            .chars()
+            .any(|ch| ch.is_whitespace() || ch.is_control());
+    let labels: Vec<_> = domain.split('.').collect();
+    let is_domain_valid = labels.len() > 1
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|ch| ch.is_alphanumeric() || ch == '-')
+        });
+    is_local_part_valid && is_domain_valid
+}
+
+fn is_locale(s: &str) -> bool {
+    let mut subtags = s.split(['-', '_']);
+    let is_language_valid = subtags.next().is_some_and(|language| {
+        (2..=8).contains(&language.len()) && language.chars().all(|ch| ch.is_ascii_alphabetic())
+    });
+    is_language_valid
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|ch| ch.is_ascii_alphanumeric())
+        })
+}
+
+fn is_zoneinfo(s: &str) -> bool {
+    s.split('/').all(|part| {
+        let mut chars = part.chars();
+        chars.next().is_some_and(|ch| ch.is_ascii_alphabetic())
+            && chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '+'))
+    })
+}
+
+fn is_date(s: &str) -> bool {
+    let is_year = |year: &str| year.len() == 4 && year.chars().all(|ch| ch.is_ascii_digit());
+    if is_year(s) {
+        return true;
+    }
+    // As per OpenID Connect, a `0000` year means that the year is omitted.
+    let s = s.strip_prefix("0000-").map_or_else(
+        || s.to_owned(),
+        |month_and_day| format!("2000-{month_and_day}"), // use a leap year
+    );
+    s.len() == 10 && NaiveDate::parse_from_str(&s, "%Y-%m-%d").is_ok()
+}
+
+/// Checks a list with tokens defined as in RFC 6749, section 3.3.
+fn is_space_list(s: &str) -> bool {
+    s.split(' ').all(|token| {
+        !token.is_empty()
+            && token
+                .chars()
+                .all(|ch| matches!(ch, '\x21' | '\x23'..='\x5b' | '\x5d'..='\x7e'))
+    })
 }
 
 // Defines:
 //
-// fn create_claims_map() -> HashMap<&'static str, StandardClaim> { /* ... */ }
+// const fn create_claims() -> &'static [(&'static str, StandardClaim)] { /* ... */ }
 // fn create_headers_map() -> HashMap<&'static str, StandardHeader> { /* ... */ }
 // const fn create_claim_categories() -> &'static [(&'static str, ClaimCategory)] { /* ... */ }
 include!(concat!(env!("OUT_DIR"), "/std_maps.rs"));
 
-static CLAIMS_MAP: Lazy<HashMap<&'static str, StandardClaim>> = Lazy::new(create_claims_map);
+/// Standard claims in the order of their declaration in `fields.toml`.
+static CLAIMS: &[(&str, StandardClaim)] = create_claims();
+static CLAIMS_MAP: Lazy<HashMap<&'static str, StandardClaim>> =
+    Lazy::new(|| CLAIMS.iter().copied().collect());
 static HEADERS_MAP: Lazy<HashMap<&'static str, StandardHeader>> = Lazy::new(create_headers_map);
 static CLAIM_CATEGORIES: &[(&str, ClaimCategory)] = create_claim_categories();
 
@@ -61,6 +245,18 @@ impl StandardClaim {
     pub fn get(name: &str) -> Option<Self> {
         CLAIMS_MAP.get(name).copied()
     }
+
+    /// Returns all standard claims together with their original names.
+    pub fn all() -> &'static [(&'static str, Self)] {
+        CLAIMS
+    }
+
+    pub fn with_html_value(self, value: Html) -> FieldWithValue {
+        FieldWithValue {
+            format: self.format,
+            ..self.field.with_html_value(value)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -71,8 +267,12 @@ impl StandardHeader {
         HEADERS_MAP[name]
     }
 
-    pub fn with_value(self, value: &dyn fmt::Display) -> FieldWithValue {
-        self.0.with_value(value)
+    pub fn get(name: &str) -> Option<Self> {
+        HEADERS_MAP.get(name).copied()
+    }
+
+    pub fn with_html_value(self, value: Html) -> FieldWithValue {
+        self.0.with_html_value(value)
     }
 
     pub fn with_code_value(self, value: &dyn fmt::Display) -> FieldWithValue {
@@ -83,9 +283,15 @@ impl StandardHeader {
 #[derive(Debug, Clone, Copy)]
 pub struct ClaimCategory {
     pub title: &'static str,
+    pub description: &'static str,
 }
 
 impl ClaimCategory {
+    /// Returns all claim categories together with their IDs.
+    pub fn all() -> &'static [(&'static str, Self)] {
+        CLAIM_CATEGORIES
+    }
+
     pub fn get(category_id: &str) -> Option<Self> {
         CLAIM_CATEGORIES.iter().find_map(|(id, category)| {
             if *id == category_id {
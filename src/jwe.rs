@@ -0,0 +1,659 @@
+//! Parsing and decryption of JSON Web Encryption (JWE) tokens in the compact serialization.
+
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::{
+    aead::{
+        consts::{U12, U16},
+        AeadInPlace,
+    },
+    AesGcm, KeyInit, Nonce, Tag,
+};
+use aes_kw::{KekAes128, KekAes192, KekAes256};
+use base64ct::{Base64UrlUnpadded, Encoding};
+use cbc::cipher::{block_padding::Pkcs7, BlockCipher, BlockDecryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use jwt_compact::jwk::JsonWebKey;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use p256::elliptic_curve::{
+    ecdh,
+    sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint},
+    AffinePoint, CurveArithmetic, FieldBytesSize, NonZeroScalar, PublicKey,
+};
+use rsa::Oaep;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use std::fmt;
+
+use crate::keys::SigningKeyInstance;
+
+/// Checks whether the provided string looks like a JWE token in the compact serialization,
+/// i.e., consists of 5 dot-separated segments.
+pub fn is_jwe(raw: &str) -> bool {
+    raw.trim().split('.').count() == 5
+}
+
+/// Errors that can occur when parsing a JWE token.
+#[derive(Debug)]
+pub enum JweParseError {
+    /// Token does not consist of 5 segments.
+    InvalidSegmentCount(usize),
+    /// Token segment is not valid base64url.
+    Base64 {
+        segment: &'static str,
+        err: base64ct::Error,
+    },
+    /// Protected header is not a JSON object.
+    MalformedHeader(serde_json::Error),
+    /// Header field is missing or has an unexpected format.
+    InvalidHeaderField(&'static str),
+}
+
+impl fmt::Display for JweParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSegmentCount(count) => {
+                write!(formatter, "expected 5 token segments, got {count}")
+            }
+            Self::Base64 { segment, err } => {
+                write!(formatter, "cannot decode {segment} from base64url: {err}")
+            }
+            Self::MalformedHeader(err) => write!(formatter, "malformed token header: {err}"),
+            Self::InvalidHeaderField(field) => write!(
+                formatter,
+                "`{field}` header field is missing or has an unexpected format"
+            ),
+        }
+    }
+}
+
+/// Errors that can occur when decrypting a JWE token.
+#[derive(Debug)]
+pub enum DecryptionError {
+    /// Key management algorithm (`alg`) is not supported.
+    UnsupportedAlgorithm(String),
+    /// Content encryption algorithm (`enc`) is not supported.
+    UnsupportedEncryption(String),
+    /// Compression algorithm (`zip`) is not supported.
+    UnsupportedCompression(String),
+    /// Key cannot be used with the key management algorithm.
+    KeyTypeMismatch { algorithm: String },
+    /// Key does not contain private parts necessary for decryption.
+    NoPrivateKey,
+    /// Key has an unexpected length.
+    InvalidKeyLength { expected: usize, actual: usize },
+    /// Encrypted key is present for direct encryption or direct key agreement.
+    UnexpectedEncryptedKey,
+    /// Encrypted content encryption key cannot be decrypted or unwrapped.
+    KeyUnwrap,
+    /// Ephemeral public key (`epk`) is missing from the header.
+    NoEphemeralKey,
+    /// Ephemeral public key (`epk`) is invalid or is not compatible with the key.
+    InvalidEphemeralKey,
+    /// Initialization vector has an unexpected length.
+    InvalidIvLength { expected: usize, actual: usize },
+    /// Authentication tag does not match; either the key is wrong, or the token is corrupted.
+    Authentication,
+    /// Decrypted content has invalid padding.
+    InvalidPadding,
+    /// Decrypted content cannot be decompressed.
+    Decompression(String),
+}
+
+impl fmt::Display for DecryptionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedAlgorithm(alg) => {
+                write!(formatter, "unsupported key management algorithm `{alg}`")
+            }
+            Self::UnsupportedEncryption(enc) => {
+                write!(
+                    formatter,
+                    "unsupported content encryption algorithm `{enc}`"
+                )
+            }
+            Self::UnsupportedCompression(zip) => {
+                write!(formatter, "unsupported compression algorithm `{zip}`")
+            }
+            Self::KeyTypeMismatch { algorithm } => write!(
+                formatter,
+                "key is not compatible with the key management algorithm `{algorithm}`"
+            ),
+            Self::NoPrivateKey => {
+                formatter.write_str("key does not contain private parts required for decryption")
+            }
+            Self::InvalidKeyLength { expected, actual } => write!(
+                formatter,
+                "invalid key length: expected {expected} bytes, got {actual}"
+            ),
+            Self::UnexpectedEncryptedKey => formatter
+                .write_str("encrypted key must be empty for direct encryption or key agreement"),
+            Self::KeyUnwrap => formatter.write_str(
+                "cannot decrypt content encryption key; the key is wrong, or the token is corrupted",
+            ),
+            Self::NoEphemeralKey => {
+                formatter.write_str("ephemeral public key (`epk`) is missing from the header")
+            }
+            Self::InvalidEphemeralKey => formatter.write_str(
+                "ephemeral public key (`epk`) is invalid or is not compatible with the key",
+            ),
+            Self::InvalidIvLength { expected, actual } => write!(
+                formatter,
+                "invalid initialization vector length: expected {expected} bytes, got {actual}"
+            ),
+            Self::Authentication => formatter.write_str(
+                "authentication tag mismatch; the key is wrong, or the token is corrupted",
+            ),
+            Self::InvalidPadding => formatter.write_str("decrypted content has invalid padding"),
+            Self::Decompression(err) => write!(formatter, "cannot decompress payload: {err}"),
+        }
+    }
+}
+
+/// Key management algorithm (the `alg` header field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyManagement {
+    /// Direct encryption with a shared symmetric key.
+    Direct,
+    /// Content encryption key wrapped with a shared symmetric key.
+    AesKeyWrap { key_len: usize },
+    /// Content encryption key encrypted with RSA-OAEP using SHA-1.
+    RsaOaep,
+    /// Content encryption key encrypted with RSA-OAEP using SHA-256.
+    RsaOaep256,
+    /// Direct key agreement using ephemeral-static ECDH.
+    EcdhEs,
+    /// Content encryption key wrapped with a key obtained via ephemeral-static ECDH.
+    EcdhEsKeyWrap { key_len: usize },
+}
+
+impl KeyManagement {
+    fn new(name: &str) -> Result<Self, DecryptionError> {
+        Ok(match name {
+            "dir" => Self::Direct,
+            "A128KW" => Self::AesKeyWrap { key_len: 16 },
+            "A192KW" => Self::AesKeyWrap { key_len: 24 },
+            "A256KW" => Self::AesKeyWrap { key_len: 32 },
+            "RSA-OAEP" => Self::RsaOaep,
+            "RSA-OAEP-256" => Self::RsaOaep256,
+            "ECDH-ES" => Self::EcdhEs,
+            "ECDH-ES+A128KW" => Self::EcdhEsKeyWrap { key_len: 16 },
+            "ECDH-ES+A192KW" => Self::EcdhEsKeyWrap { key_len: 24 },
+            "ECDH-ES+A256KW" => Self::EcdhEsKeyWrap { key_len: 32 },
+            _ => return Err(DecryptionError::UnsupportedAlgorithm(name.to_owned())),
+        })
+    }
+
+    fn is_compatible(self, key: &SigningKeyInstance) -> bool {
+        match self {
+            Self::Direct | Self::AesKeyWrap { .. } => {
+                matches!(key, SigningKeyInstance::Symmetric(_))
+            }
+            Self::RsaOaep | Self::RsaOaep256 => matches!(key, SigningKeyInstance::Rsa(_)),
+            Self::EcdhEs | Self::EcdhEsKeyWrap { .. } => matches!(
+                key,
+                SigningKeyInstance::P256(_)
+                    | SigningKeyInstance::P384(_)
+                    | SigningKeyInstance::P521(_)
+            ),
+        }
+    }
+
+    /// Unwraps the content encryption key using the AES key wrap algorithm (RFC 3394).
+    fn unwrap_key(kek: &[u8], wrapped_key: &[u8]) -> Result<Vec<u8>, DecryptionError> {
+        let unwrapped = match kek.len() {
+            16 => KekAes128::try_from(kek).map(|kek| kek.unwrap_vec(wrapped_key)),
+            24 => KekAes192::try_from(kek).map(|kek| kek.unwrap_vec(wrapped_key)),
+            32 => KekAes256::try_from(kek).map(|kek| kek.unwrap_vec(wrapped_key)),
+            _ => unreachable!(),
+        };
+        unwrapped
+            .expect("key length is checked")
+            .map_err(|_| DecryptionError::KeyUnwrap)
+    }
+
+    /// Derives a key from the ECDH shared secret using the Concat KDF (RFC 7518, section 4.6.2).
+    /// `other_info` contains the algorithm ID, `PartyUInfo` and `PartyVInfo` in this order;
+    /// each of them is prefixed with its length.
+    fn concat_kdf(shared_secret: &[u8], other_info: [&[u8]; 3], key_len: usize) -> Vec<u8> {
+        let key_bit_len = u32::try_from(key_len * 8).expect("length overflow");
+        let mut output = Vec::with_capacity(key_len + 32);
+        let mut counter = 1_u32;
+        while output.len() < key_len {
+            let mut digest = Sha256::new()
+                .chain_update(counter.to_be_bytes())
+                .chain_update(shared_secret);
+            for part in other_info {
+                let len = u32::try_from(part.len()).expect("length overflow");
+                digest = digest.chain_update(len.to_be_bytes()).chain_update(part);
+            }
+            let digest = digest.chain_update(key_bit_len.to_be_bytes()).finalize();
+            output.extend_from_slice(&digest);
+            counter += 1;
+        }
+        output.truncate(key_len);
+        output
+    }
+
+    fn diffie_hellman<C>(
+        secret: &NonZeroScalar<C>,
+        ephemeral_key: &JsonWebKey<'_>,
+    ) -> Result<Vec<u8>, DecryptionError>
+    where
+        C: CurveArithmetic,
+        AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+        FieldBytesSize<C>: ModulusSize,
+    {
+        let JsonWebKey::EllipticCurve { x, y, .. } = ephemeral_key else {
+            return Err(DecryptionError::InvalidEphemeralKey);
+        };
+        let mut uncompressed_point = Vec::with_capacity(1 + x.len() + y.len());
+        uncompressed_point.push(4);
+        uncompressed_point.extend_from_slice(x);
+        uncompressed_point.extend_from_slice(y);
+        let public_key = PublicKey::<C>::from_sec1_bytes(&uncompressed_point)
+            .map_err(|_| DecryptionError::InvalidEphemeralKey)?;
+
+        let shared_secret = ecdh::diffie_hellman(secret, public_key.as_affine());
+        Ok(shared_secret.raw_secret_bytes().to_vec())
+    }
+}
+
+/// Content encryption algorithm (the `enc` header field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentEncryption {
+    A128Gcm,
+    A192Gcm,
+    A256Gcm,
+    A128CbcHs256,
+    A192CbcHs384,
+    A256CbcHs512,
+}
+
+impl ContentEncryption {
+    const GCM_IV_LEN: usize = 12;
+    const CBC_IV_LEN: usize = 16;
+
+    fn new(name: &str) -> Result<Self, DecryptionError> {
+        Ok(match name {
+            "A128GCM" => Self::A128Gcm,
+            "A192GCM" => Self::A192Gcm,
+            "A256GCM" => Self::A256Gcm,
+            "A128CBC-HS256" => Self::A128CbcHs256,
+            "A192CBC-HS384" => Self::A192CbcHs384,
+            "A256CBC-HS512" => Self::A256CbcHs512,
+            _ => return Err(DecryptionError::UnsupportedEncryption(name.to_owned())),
+        })
+    }
+
+    fn key_len(self) -> usize {
+        match self {
+            Self::A128Gcm => 16,
+            Self::A192Gcm => 24,
+            Self::A256Gcm | Self::A128CbcHs256 => 32,
+            Self::A192CbcHs384 => 48,
+            Self::A256CbcHs512 => 64,
+        }
+    }
+
+    fn decrypt(self, key: &[u8], parts: &EncryptedParts<'_>) -> Result<Vec<u8>, DecryptionError> {
+        if key.len() != self.key_len() {
+            return Err(DecryptionError::InvalidKeyLength {
+                expected: self.key_len(),
+                actual: key.len(),
+            });
+        }
+        match self {
+            Self::A128Gcm => Self::decrypt_gcm::<Aes128>(key, parts),
+            Self::A192Gcm => Self::decrypt_gcm::<Aes192>(key, parts),
+            Self::A256Gcm => Self::decrypt_gcm::<Aes256>(key, parts),
+            Self::A128CbcHs256 => Self::decrypt_cbc_hmac::<Aes128, Hmac<Sha256>>(key, parts),
+            Self::A192CbcHs384 => Self::decrypt_cbc_hmac::<Aes192, Hmac<Sha384>>(key, parts),
+            Self::A256CbcHs512 => Self::decrypt_cbc_hmac::<Aes256, Hmac<Sha512>>(key, parts),
+        }
+    }
+
+    /// Decrypts content using AES in the Galois / counter mode (RFC 7518, section 5.3).
+    fn decrypt_gcm<C>(key: &[u8], parts: &EncryptedParts<'_>) -> Result<Vec<u8>, DecryptionError>
+    where
+        AesGcm<C, U12>: KeyInit + AeadInPlace<NonceSize = U12, TagSize = U16>,
+    {
+        if parts.iv.len() != Self::GCM_IV_LEN {
+            return Err(DecryptionError::InvalidIvLength {
+                expected: Self::GCM_IV_LEN,
+                actual: parts.iv.len(),
+            });
+        }
+        if parts.tag.len() != 16 {
+            return Err(DecryptionError::Authentication);
+        }
+
+        let cipher = AesGcm::<C, U12>::new_from_slice(key).expect("key length is checked");
+        let nonce: [u8; Self::GCM_IV_LEN] = parts.iv.try_into().expect("IV length is checked");
+        let tag: [u8; 16] = parts.tag.try_into().expect("tag length is checked");
+        let mut buffer = parts.ciphertext.to_vec();
+        cipher
+            .decrypt_in_place_detached(
+                &Nonce::<U12>::from(nonce),
+                parts.aad,
+                &mut buffer,
+                &Tag::from(tag),
+            )
+            .map_err(|_| DecryptionError::Authentication)?;
+        Ok(buffer)
+    }
+
+    /// Decrypts content using AES in the cipher block chaining mode with HMAC-based
+    /// authentication (RFC 7518, section 5.2).
+    fn decrypt_cbc_hmac<C, M>(
+        key: &[u8],
+        parts: &EncryptedParts<'_>,
+    ) -> Result<Vec<u8>, DecryptionError>
+    where
+        C: BlockCipher + BlockDecryptMut + KeyInit,
+        M: Mac + KeyInit,
+    {
+        if parts.iv.len() != Self::CBC_IV_LEN {
+            return Err(DecryptionError::InvalidIvLength {
+                expected: Self::CBC_IV_LEN,
+                actual: parts.iv.len(),
+            });
+        }
+
+        // The first half of the key is used for authentication, and the second one
+        // for encryption. The authentication tag is truncated to the half of the MAC output.
+        let (mac_key, enc_key) = key.split_at(key.len() / 2);
+        if parts.tag.len() != mac_key.len() {
+            return Err(DecryptionError::Authentication);
+        }
+        let aad_bit_len = (parts.aad.len() as u64) * 8;
+        let mut mac = <M as Mac>::new_from_slice(mac_key).expect("HMAC accepts keys of any size");
+        mac.update(parts.aad);
+        mac.update(parts.iv);
+        mac.update(parts.ciphertext);
+        mac.update(&aad_bit_len.to_be_bytes());
+        mac.verify_truncated_left(parts.tag)
+            .map_err(|_| DecryptionError::Authentication)?;
+
+        let decryptor = cbc::Decryptor::<C>::new_from_slices(enc_key, parts.iv)
+            .expect("key and IV lengths are checked");
+        decryptor
+            .decrypt_padded_vec_mut::<Pkcs7>(parts.ciphertext)
+            .map_err(|_| DecryptionError::InvalidPadding)
+    }
+}
+
+/// Parts of the token used in content decryption.
+#[derive(Debug)]
+struct EncryptedParts<'a> {
+    aad: &'a [u8],
+    iv: &'a [u8],
+    ciphertext: &'a [u8],
+    tag: &'a [u8],
+}
+
+/// Protected header of an encrypted token.
+#[derive(Debug, Clone)]
+pub struct JweHeader {
+    /// Key management algorithm (`alg`).
+    pub algorithm: String,
+    /// Content encryption algorithm (`enc`).
+    pub encryption: String,
+    /// Compression algorithm applied to the plaintext before encryption (`zip`).
+    pub compression: Option<String>,
+    /// Key ID (`kid`).
+    pub key_id: Option<String>,
+    /// Token type (`typ`).
+    pub token_type: Option<String>,
+    /// Payload content type (`cty`). Set to `JWT` for nested tokens.
+    pub content_type: Option<String>,
+    /// Ephemeral public key (`epk`) for ECDH-based key agreement.
+    pub ephemeral_key: Option<JsonWebKey<'static>>,
+    /// Agreement `PartyUInfo` (`apu`) for ECDH-based key agreement.
+    pub party_u_info: Option<Vec<u8>>,
+    /// Agreement `PartyVInfo` (`apv`) for ECDH-based key agreement.
+    pub party_v_info: Option<Vec<u8>>,
+    /// Header extensions that must be understood by the recipient (`crit`).
+    pub critical: Vec<String>,
+}
+
+impl JweHeader {
+    fn new(fields: &serde_json::Map<String, serde_json::Value>) -> Result<Self, JweParseError> {
+        let required_str = |name: &'static str| {
+            fields
+                .get(name)
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_owned)
+                .ok_or(JweParseError::InvalidHeaderField(name))
+        };
+        let optional_str = |name: &'static str| match fields.get(name) {
+            None => Ok(None),
+            Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(JweParseError::InvalidHeaderField(name)),
+        };
+
+        let optional_bytes = |name: &'static str| {
+            optional_str(name)?
+                .map(|value| {
+                    Base64UrlUnpadded::decode_vec(&value)
+                        .map_err(|_| JweParseError::InvalidHeaderField(name))
+                })
+                .transpose()
+        };
+        let ephemeral_key = fields
+            .get("epk")
+            .map(|value| {
+                serde_json::from_value(value.clone())
+                    .map_err(|_| JweParseError::InvalidHeaderField("epk"))
+            })
+            .transpose()?;
+        let critical = fields
+            .get("crit")
+            .map(|value| {
+                serde_json::from_value(value.clone())
+                    .map_err(|_| JweParseError::InvalidHeaderField("crit"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self {
+            algorithm: required_str("alg")?,
+            encryption: required_str("enc")?,
+            compression: optional_str("zip")?,
+            key_id: optional_str("kid")?,
+            token_type: optional_str("typ")?,
+            content_type: optional_str("cty")?,
+            ephemeral_key,
+            party_u_info: optional_bytes("apu")?,
+            party_v_info: optional_bytes("apv")?,
+            critical,
+        })
+    }
+}
+
+/// JWE token in the compact serialization.
+#[derive(Debug, Clone)]
+pub struct EncryptedToken {
+    header: JweHeader,
+    /// Base64url-encoded protected header, which is used as additional authenticated data.
+    encoded_header: String,
+    encrypted_key: Vec<u8>,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+}
+
+impl EncryptedToken {
+    /// The only standard compression algorithm.
+    const DEFLATE: &'static str = "DEF";
+    /// Maximum length of the decompressed payload. Protects against decompression bombs.
+    const MAX_DECOMPRESSED_LEN: usize = 1 << 20;
+
+    /// Parses a token from the compact serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed.
+    pub fn new(raw: &str) -> Result<Self, JweParseError> {
+        let segments: Vec<_> = raw.trim().split('.').collect();
+        let [encoded_header, encrypted_key, iv, ciphertext, tag] = segments[..] else {
+            return Err(JweParseError::InvalidSegmentCount(segments.len()));
+        };
+
+        let header = Self::decode(encoded_header, "header")?;
+        let header: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&header).map_err(JweParseError::MalformedHeader)?;
+        Ok(Self {
+            header: JweHeader::new(&header)?,
+            encoded_header: encoded_header.to_owned(),
+            encrypted_key: Self::decode(encrypted_key, "encrypted key")?,
+            iv: Self::decode(iv, "initialization vector")?,
+            ciphertext: Self::decode(ciphertext, "ciphertext")?,
+            tag: Self::decode(tag, "authentication tag")?,
+        })
+    }
+
+    fn decode(segment: &str, name: &'static str) -> Result<Vec<u8>, JweParseError> {
+        Base64UrlUnpadded::decode_vec(segment)
+            .map_err(|err| JweParseError::Base64 { segment: name, err })
+    }
+
+    pub fn header(&self) -> &JweHeader {
+        &self.header
+    }
+
+    /// Checks whether the specified key can be used to decrypt this token.
+    pub fn is_compatible(&self, key: &SigningKeyInstance) -> bool {
+        KeyManagement::new(&self.header.algorithm)
+            .is_ok_and(|key_management| key_management.is_compatible(key))
+    }
+
+    /// Decrypts the token payload. If the payload is compressed, it is decompressed as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token algorithms are not supported, the key is not compatible
+    /// with the token, or decryption fails.
+    pub fn decrypt(&self, key: &SigningKeyInstance) -> Result<Vec<u8>, DecryptionError> {
+        let key_management = KeyManagement::new(&self.header.algorithm)?;
+        let encryption = ContentEncryption::new(&self.header.encryption)?;
+        match self.header.compression.as_deref() {
+            None | Some(Self::DEFLATE) => { /* supported */ }
+            Some(compression) => {
+                return Err(DecryptionError::UnsupportedCompression(
+                    compression.to_owned(),
+                ));
+            }
+        }
+        if !key_management.is_compatible(key) {
+            return Err(DecryptionError::KeyTypeMismatch {
+                algorithm: self.header.algorithm.clone(),
+            });
+        }
+
+        let content_key = self.content_key(key_management, encryption, key)?;
+        let parts = EncryptedParts {
+            aad: self.encoded_header.as_bytes(),
+            iv: &self.iv,
+            ciphertext: &self.ciphertext,
+            tag: &self.tag,
+        };
+        let plaintext = encryption.decrypt(&content_key, &parts)?;
+
+        if self.header.compression.is_some() {
+            decompress_to_vec_with_limit(&plaintext, Self::MAX_DECOMPRESSED_LEN)
+                .map_err(|err| DecryptionError::Decompression(err.to_string()))
+        } else {
+            Ok(plaintext)
+        }
+    }
+
+    /// Determines the content encryption key. Assumes that the key is compatible
+    /// with the key management algorithm.
+    fn content_key(
+        &self,
+        key_management: KeyManagement,
+        encryption: ContentEncryption,
+        key: &SigningKeyInstance,
+    ) -> Result<Vec<u8>, DecryptionError> {
+        match (key_management, key) {
+            (KeyManagement::Direct, SigningKeyInstance::Symmetric(secret)) => {
+                self.ensure_no_encrypted_key()?;
+                Ok(secret.to_vec())
+            }
+            (KeyManagement::AesKeyWrap { key_len }, SigningKeyInstance::Symmetric(secret)) => {
+                if secret.len() != key_len {
+                    return Err(DecryptionError::InvalidKeyLength {
+                        expected: key_len,
+                        actual: secret.len(),
+                    });
+                }
+                KeyManagement::unwrap_key(secret, &self.encrypted_key)
+            }
+            (KeyManagement::RsaOaep, SigningKeyInstance::Rsa(key)) => key
+                .decrypt(Oaep::new::<Sha1>(), &self.encrypted_key)
+                .map_err(|_| DecryptionError::KeyUnwrap),
+            (KeyManagement::RsaOaep256, SigningKeyInstance::Rsa(key)) => key
+                .decrypt(Oaep::new::<Sha256>(), &self.encrypted_key)
+                .map_err(|_| DecryptionError::KeyUnwrap),
+            (KeyManagement::EcdhEs, _) => {
+                self.ensure_no_encrypted_key()?;
+                self.derive_key(key, &self.header.encryption, encryption.key_len())
+            }
+            (KeyManagement::EcdhEsKeyWrap { key_len }, _) => {
+                let kek = self.derive_key(key, &self.header.algorithm, key_len)?;
+                KeyManagement::unwrap_key(&kek, &self.encrypted_key)
+            }
+            _ => unreachable!("key compatibility is checked"),
+        }
+    }
+
+    fn ensure_no_encrypted_key(&self) -> Result<(), DecryptionError> {
+        if self.encrypted_key.is_empty() {
+            Ok(())
+        } else {
+            Err(DecryptionError::UnexpectedEncryptedKey)
+        }
+    }
+
+    /// Derives a key using ECDH with the ephemeral key from the header.
+    fn derive_key(
+        &self,
+        key: &SigningKeyInstance,
+        algorithm_id: &str,
+        key_len: usize,
+    ) -> Result<Vec<u8>, DecryptionError> {
+        let ephemeral_key = self
+            .header
+            .ephemeral_key
+            .as_ref()
+            .ok_or(DecryptionError::NoEphemeralKey)?;
+        let JsonWebKey::EllipticCurve { curve, .. } = ephemeral_key else {
+            return Err(DecryptionError::InvalidEphemeralKey);
+        };
+
+        let shared_secret = match (key, curve.as_ref()) {
+            (SigningKeyInstance::P256(key), "P-256") => {
+                KeyManagement::diffie_hellman(key.as_nonzero_scalar(), ephemeral_key)?
+            }
+            (SigningKeyInstance::P384(key), "P-384") => {
+                KeyManagement::diffie_hellman(key.as_nonzero_scalar(), ephemeral_key)?
+            }
+            (SigningKeyInstance::P521(key), "P-521") => {
+                KeyManagement::diffie_hellman(key.as_nonzero_scalar(), ephemeral_key)?
+            }
+            _ => return Err(DecryptionError::InvalidEphemeralKey),
+        };
+
+        let other_info = [
+            algorithm_id.as_bytes(),
+            self.header.party_u_info.as_deref().unwrap_or_default(),
+            self.header.party_v_info.as_deref().unwrap_or_default(),
+        ];
+        Ok(KeyManagement::concat_kdf(
+            &shared_secret,
+            other_info,
+            key_len,
+        ))
+    }
+}
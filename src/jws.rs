@@ -0,0 +1,256 @@
+//! Parsing of signed tokens in the JWS JSON serialization (RFC 7515, section 7.2),
+//! both in the general and the flattened syntax.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+
+use std::fmt;
+
+use crate::keys::SignatureInput;
+
+type JsonObject = serde_json::Map<String, serde_json::Value>;
+
+/// Checks whether the provided string looks like a token in the JSON serialization.
+pub fn is_json_serialization(raw: &str) -> bool {
+    raw.trim_start().starts_with('{')
+}
+
+/// Errors that can occur when parsing a token in the JWS JSON serialization.
+#[derive(Debug)]
+pub enum JsonParseError {
+    /// Token is not a JSON object.
+    Malformed(serde_json::Error),
+    /// Field is missing or has an unexpected type.
+    InvalidField(&'static str),
+    /// Field is not valid base64url.
+    Base64 {
+        field: &'static str,
+        err: base64ct::Error,
+    },
+    /// Protected header is not a JSON object.
+    MalformedHeader(serde_json::Error),
+    /// Token has no signatures.
+    NoSignatures,
+    /// Header field is present both in the protected and unprotected header.
+    DuplicateHeaderField(String),
+    /// Header field of a signature is missing or has an unexpected format.
+    InvalidHeaderField { index: usize, field: &'static str },
+}
+
+impl fmt::Display for JsonParseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(err) => write!(formatter, "malformed JWS JSON: {err}"),
+            Self::InvalidField(field) => write!(
+                formatter,
+                "`{field}` field is missing or has an unexpected type"
+            ),
+            Self::Base64 { field, err } => {
+                write!(formatter, "cannot decode `{field}` from base64url: {err}")
+            }
+            Self::MalformedHeader(err) => write!(formatter, "malformed protected header: {err}"),
+            Self::NoSignatures => formatter.write_str("token contains no signatures"),
+            Self::DuplicateHeaderField(field) => write!(
+                formatter,
+                "`{field}` header field is present both in protected and unprotected headers"
+            ),
+            Self::InvalidHeaderField { index, field } => write!(
+                formatter,
+                "`{field}` header field of signature #{} is missing or has an unexpected format",
+                index + 1
+            ),
+        }
+    }
+}
+
+/// Single signature of a [`JsonToken`].
+#[derive(Debug, Clone)]
+pub struct JsonSignature {
+    /// Decoded protected header. Empty if the signature has no protected header.
+    pub protected_header: JsonObject,
+    /// Unprotected header. Empty if the signature has no unprotected header.
+    pub unprotected_header: JsonObject,
+    algorithm: String,
+    key_id: Option<String>,
+    critical: Vec<String>,
+    signed_data: String,
+    signature: Vec<u8>,
+}
+
+impl JsonSignature {
+    fn new(
+        index: usize,
+        object: &JsonObject,
+        encoded_payload: &str,
+    ) -> Result<Self, JsonParseError> {
+        let encoded_protected_header = match object.get("protected") {
+            None => "",
+            Some(serde_json::Value::String(header)) => header.as_str(),
+            Some(_) => return Err(JsonParseError::InvalidField("protected")),
+        };
+        let protected_header = if encoded_protected_header.is_empty() {
+            JsonObject::new()
+        } else {
+            let header = decode(encoded_protected_header, "protected")?;
+            serde_json::from_slice(&header).map_err(JsonParseError::MalformedHeader)?
+        };
+        let unprotected_header = match object.get("header") {
+            None => JsonObject::new(),
+            Some(serde_json::Value::Object(header)) => header.clone(),
+            Some(_) => return Err(JsonParseError::InvalidField("header")),
+        };
+        if let Some(field) = protected_header
+            .keys()
+            .find(|&field| unprotected_header.contains_key(field))
+        {
+            return Err(JsonParseError::DuplicateHeaderField(field.clone()));
+        }
+
+        let signature = match object.get("signature") {
+            Some(serde_json::Value::String(signature)) => decode(signature, "signature")?,
+            _ => return Err(JsonParseError::InvalidField("signature")),
+        };
+
+        let header_str = |field: &'static str| {
+            let value = protected_header
+                .get(field)
+                .or_else(|| unprotected_header.get(field));
+            match value {
+                None => Ok(None),
+                Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+                Some(_) => Err(JsonParseError::InvalidHeaderField { index, field }),
+            }
+        };
+        let algorithm = header_str("alg")?.ok_or(JsonParseError::InvalidHeaderField {
+            index,
+            field: "alg",
+        })?;
+        let key_id = header_str("kid")?;
+        // As per RFC 7515, section 4.1.11, `crit` must be integrity-protected.
+        if unprotected_header.contains_key("crit") {
+            return Err(JsonParseError::InvalidHeaderField {
+                index,
+                field: "crit",
+            });
+        }
+        let critical = protected_header
+            .get("crit")
+            .map(|value| serde_json::from_value(value.clone()))
+            .transpose()
+            .map_err(|_| JsonParseError::InvalidHeaderField {
+                index,
+                field: "crit",
+            })?
+            .unwrap_or_default();
+
+        Ok(Self {
+            protected_header,
+            unprotected_header,
+            algorithm,
+            key_id,
+            critical,
+            signed_data: format!("{encoded_protected_header}.{encoded_payload}"),
+            signature,
+        })
+    }
+
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Returns names of critical header fields (`crit`).
+    pub fn critical_fields(&self) -> &[String] {
+        &self.critical
+    }
+
+    pub fn signature_bytes(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Returns the input for signature verification.
+    pub fn signature_input(&self) -> SignatureInput<'_> {
+        SignatureInput {
+            algorithm: &self.algorithm,
+            key_id: self.key_id.as_deref(),
+            signed_data: self.signed_data.as_bytes(),
+            signature: &self.signature,
+        }
+    }
+}
+
+/// Signed token in the JWS JSON serialization. The token may contain multiple signatures
+/// over the same payload.
+#[derive(Debug, Clone)]
+pub struct JsonToken {
+    is_flattened: bool,
+    payload: Vec<u8>,
+    signatures: Vec<JsonSignature>,
+}
+
+impl JsonToken {
+    /// Parses a token from the JSON serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed.
+    pub fn new(raw: &str) -> Result<Self, JsonParseError> {
+        let object: JsonObject = serde_json::from_str(raw).map_err(JsonParseError::Malformed)?;
+        let encoded_payload = match object.get("payload") {
+            Some(serde_json::Value::String(payload)) => payload.as_str(),
+            _ => return Err(JsonParseError::InvalidField("payload")),
+        };
+        let payload = decode(encoded_payload, "payload")?;
+
+        let (is_flattened, signatures) = match object.get("signatures") {
+            None => {
+                let signature = JsonSignature::new(0, &object, encoded_payload)?;
+                (true, vec![signature])
+            }
+            Some(serde_json::Value::Array(signatures)) => {
+                let signatures = signatures
+                    .iter()
+                    .enumerate()
+                    .map(|(index, signature)| {
+                        let serde_json::Value::Object(signature) = signature else {
+                            return Err(JsonParseError::InvalidField("signatures"));
+                        };
+                        JsonSignature::new(index, signature, encoded_payload)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                (false, signatures)
+            }
+            Some(_) => return Err(JsonParseError::InvalidField("signatures")),
+        };
+        if signatures.is_empty() {
+            return Err(JsonParseError::NoSignatures);
+        }
+
+        Ok(Self {
+            is_flattened,
+            payload,
+            signatures,
+        })
+    }
+
+    /// Checks whether the token uses the flattened syntax (i.e., has a single signature
+    /// without the `signatures` array).
+    pub fn is_flattened(&self) -> bool {
+        self.is_flattened
+    }
+
+    /// Returns the payload shared by all signatures.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn signatures(&self) -> &[JsonSignature] {
+        &self.signatures
+    }
+}
+
+fn decode(value: &str, field: &'static str) -> Result<Vec<u8>, JsonParseError> {
+    Base64UrlUnpadded::decode_vec(value).map_err(|err| JsonParseError::Base64 { field, err })
+}
@@ -1,23 +1,37 @@
+use base64ct::{Base64UrlUnpadded, Encoding};
 use chrono::Duration;
-use ed25519_compact::PublicKey as Ed25519PublicKey;
+use ed25519_compact::{
+    KeyPair as Ed25519KeyPair, PublicKey as Ed25519PublicKey, SecretKey as Ed25519SecretKey,
+    Seed as Ed25519Seed,
+};
 use getrandom::getrandom;
 use jwt_compact::{
     alg::{
-        Ed25519, Es256k, Hs256, Hs256Key, Hs384, Hs384Key, Hs512, Hs512Key, Rsa, RsaPublicKey,
-        SecretBytes,
+        Ed25519, Es256, Es256k, Hs256, Hs256Key, Hs384, Hs384Key, Hs512, Hs512Key, Rsa,
+        RsaPrivateKey, RsaPublicKey, SecretBytes,
     },
     jwk::{JsonWebKey, JwkError},
-    AlgorithmExt, Claims, Header, TimeOptions, Token, UntrustedToken, ValidationError,
+    Algorithm, AlgorithmExt, AlgorithmSignature, Claims, CreationError, Header, TimeOptions, Token,
+    UntrustedToken, ValidationError,
 };
-use k256::ecdsa::VerifyingKey as K256PublicKey;
+use k256::ecdsa::{SigningKey as K256SigningKey, VerifyingKey as K256PublicKey};
+use p256::ecdsa::{SigningKey as P256SigningKey, VerifyingKey as P256PublicKey};
 use sha2::Sha256;
 use uuid::Uuid;
 use wasm_bindgen::UnwrapThrowExt;
 
-use std::convert::TryFrom;
+use std::{convert::TryFrom, fmt};
+
+use crate::{
+    alg::{Es384, Es512, P384PublicKey, P521PublicKey},
+    jwe::{DecryptionError, EncryptedToken},
+    rng::Csprng,
+    x509::CertificateThumbprints,
+};
 
 pub type GenericToken = Token<serde_json::Value>;
 pub type GenericClaims = Claims<serde_json::Value>;
+pub type GenericHeader = Header<serde_json::Map<String, serde_json::Value>>;
 
 #[derive(Debug)]
 pub enum KeyInstance {
@@ -25,6 +39,9 @@ pub enum KeyInstance {
     Rsa(RsaPublicKey),
     Ed25519(Ed25519PublicKey),
     K256(K256PublicKey),
+    P256(P256PublicKey),
+    P384(P384PublicKey),
+    P521(P521PublicKey),
 }
 
 impl KeyInstance {
@@ -39,15 +56,47 @@ impl KeyInstance {
             }
             JsonWebKey::Rsa { .. } => RsaPublicKey::try_from(jwk).map(Self::Rsa),
             JsonWebKey::KeyPair { .. } => Ed25519PublicKey::try_from(jwk).map(Self::Ed25519),
-            JsonWebKey::EllipticCurve { .. } => K256PublicKey::try_from(jwk).map(Self::K256),
+            JsonWebKey::EllipticCurve { curve, .. } => match curve.as_ref() {
+                "P-256" => P256PublicKey::try_from(jwk).map(Self::P256),
+                "P-384" => P384PublicKey::try_from(jwk).map(Self::P384),
+                "P-521" => P521PublicKey::try_from(jwk).map(Self::P521),
+                _ => K256PublicKey::try_from(jwk).map(Self::K256),
+            },
             _ => unreachable!(),
         }
     }
 
-    pub fn random_key() -> Hs256Key {
-        let mut bytes = [0_u8; 64];
-        getrandom(&mut bytes).expect_throw("cannot access CSPRNG");
-        Hs256Key::new(bytes)
+    /// Returns the JWK presentation of this key.
+    pub fn to_jwk(&self) -> JsonWebKey<'_> {
+        match self {
+            Self::Symmetric(secret) => JsonWebKey::Symmetric {
+                secret: SecretBytes::borrowed(secret),
+            },
+            Self::Rsa(key) => key.into(),
+            Self::Ed25519(key) => key.into(),
+            Self::K256(key) => key.into(),
+            Self::P256(key) => key.into(),
+            Self::P384(key) => key.into(),
+            Self::P521(key) => key.into(),
+        }
+    }
+
+    /// Returns the SHA-256 key thumbprint as per RFC 7638, encoded in base64url.
+    pub fn thumbprint(&self) -> String {
+        Base64UrlUnpadded::encode_string(&self.to_jwk().thumbprint::<Sha256>())
+    }
+
+    /// Checks whether this key can be used to verify tokens with the specified algorithm.
+    pub fn is_compatible(&self, alg: &str) -> bool {
+        match self {
+            Self::Symmetric(_) => matches!(alg, "HS256" | "HS384" | "HS512"),
+            Self::Rsa(_) => alg.parse::<Rsa>().is_ok(),
+            Self::Ed25519(_) => alg == "EdDSA",
+            Self::K256(_) => alg == "ES256K",
+            Self::P256(_) => alg == "ES256",
+            Self::P384(_) => alg == "ES384",
+            Self::P521(_) => alg == "ES512",
+        }
     }
 
     /// # Errors
@@ -82,11 +131,288 @@ impl KeyInstance {
             Self::Ed25519(key) => Ed25519.validator(key).validate(token),
 
             Self::K256(key) => Es256k::<Sha256>::default().validator(key).validate(token),
+
+            Self::P256(key) => Es256.validator(key).validate(token),
+            Self::P384(key) => Es384.validator(key).validate(token),
+            Self::P521(key) => Es512.validator(key).validate(token),
         }
     }
 
-    pub fn random_token(key: &Hs256Key) -> String {
-        let header = Header::empty()
+    /// Verifies a raw signature over `message`. Unlike [`Self::verify_token()`], this method
+    /// does not place any requirements on the signed payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is not valid, or if the algorithm is incompatible
+    /// with this key.
+    pub fn verify_signature(
+        &self,
+        alg: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), ValidationError> {
+        match self {
+            Self::Symmetric(secret) => match alg {
+                "HS256" => verify_raw(&Hs256, &Hs256Key::new(secret), alg, message, signature),
+                "HS384" => verify_raw(&Hs384, &Hs384Key::new(secret), alg, message, signature),
+                "HS512" => verify_raw(&Hs512, &Hs512Key::new(secret), alg, message, signature),
+                _ => Err(ValidationError::AlgorithmMismatch {
+                    expected: "HS256, HS384 or HS512".to_owned(),
+                    actual: alg.to_owned(),
+                }),
+            },
+
+            Self::Rsa(key) => {
+                let rsa = alg
+                    .parse::<Rsa>()
+                    .map_err(|_| ValidationError::AlgorithmMismatch {
+                        expected: "RS* or PS* algorithm".to_owned(),
+                        actual: alg.to_owned(),
+                    })?;
+                verify_raw(&rsa, key, alg, message, signature)
+            }
+
+            Self::Ed25519(key) => verify_raw(&Ed25519, key, alg, message, signature),
+            Self::K256(key) => {
+                verify_raw(&Es256k::<Sha256>::default(), key, alg, message, signature)
+            }
+            Self::P256(key) => verify_raw(&Es256, key, alg, message, signature),
+            Self::P384(key) => verify_raw(&Es384, key, alg, message, signature),
+            Self::P521(key) => verify_raw(&Es512, key, alg, message, signature),
+        }
+    }
+}
+
+/// Mirrors signature checks performed by `jwt-compact` validators.
+fn verify_raw<A: Algorithm>(
+    algorithm: &A,
+    key: &A::VerifyingKey,
+    alg: &str,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), ValidationError> {
+    let expected_alg = algorithm.name();
+    if expected_alg != alg {
+        return Err(ValidationError::AlgorithmMismatch {
+            expected: expected_alg.into_owned(),
+            actual: alg.to_owned(),
+        });
+    }
+    if let Some(expected_len) = A::Signature::LENGTH {
+        if signature.len() != expected_len.get() {
+            return Err(ValidationError::InvalidSignatureLen {
+                expected: expected_len.get(),
+                actual: signature.len(),
+            });
+        }
+    }
+
+    let signature =
+        A::Signature::try_from_slice(signature).map_err(ValidationError::MalformedSignature)?;
+    if algorithm.verify_signature(&signature, key, message) {
+        Ok(())
+    } else {
+        Err(ValidationError::InvalidSignature)
+    }
+}
+
+/// Errors that can occur when signing a token.
+#[derive(Debug)]
+pub enum SigningError {
+    /// Token algorithm is not compatible with the signing key.
+    AlgorithmMismatch {
+        /// Algorithm(s) compatible with the key.
+        expected: String,
+        /// Requested algorithm.
+        actual: String,
+    },
+    /// Error creating the token.
+    Creation(CreationError),
+}
+
+impl fmt::Display for SigningError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlgorithmMismatch { expected, actual } => write!(
+                formatter,
+                "token algorithm ({actual}) is not compatible with the key (expected {expected})"
+            ),
+            Self::Creation(err) => write!(formatter, "cannot create token: {err}"),
+        }
+    }
+}
+
+impl From<CreationError> for SigningError {
+    fn from(err: CreationError) -> Self {
+        Self::Creation(err)
+    }
+}
+
+/// Type of a key pair that can be generated with [`SigningKeyInstance::generate()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyPairType {
+    #[default]
+    Ed25519,
+    Secp256k1,
+    P256,
+    P384,
+    P521,
+    Rsa2048,
+    Rsa3072,
+    Rsa4096,
+}
+
+impl KeyPairType {
+    pub const ALL: [Self; 8] = [
+        Self::Ed25519,
+        Self::Secp256k1,
+        Self::P256,
+        Self::P384,
+        Self::P521,
+        Self::Rsa2048,
+        Self::Rsa3072,
+        Self::Rsa4096,
+    ];
+
+    /// Returns a machine-readable identifier of this key type.
+    pub fn value(self) -> &'static str {
+        match self {
+            Self::Ed25519 => "ed25519",
+            Self::Secp256k1 => "secp256k1",
+            Self::P256 => "p256",
+            Self::P384 => "p384",
+            Self::P521 => "p521",
+            Self::Rsa2048 => "rsa2048",
+            Self::Rsa3072 => "rsa3072",
+            Self::Rsa4096 => "rsa4096",
+        }
+    }
+
+    pub fn from_value(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|ty| ty.value() == value)
+    }
+
+    /// Returns a human-readable label for this key type.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ed25519 => "Ed25519 (EdDSA)",
+            Self::Secp256k1 => "secp256k1 (ES256K)",
+            Self::P256 => "P-256 (ES256)",
+            Self::P384 => "P-384 (ES384)",
+            Self::P521 => "P-521 (ES512)",
+            Self::Rsa2048 => "RSA, 2048 bits",
+            Self::Rsa3072 => "RSA, 3072 bits",
+            Self::Rsa4096 => "RSA, 4096 bits",
+        }
+    }
+}
+
+/// Signing key for one of supported algorithms.
+pub enum SigningKeyInstance {
+    Symmetric(SecretBytes<'static>),
+    Rsa(Box<RsaPrivateKey>),
+    Ed25519(Ed25519SecretKey),
+    K256(K256SigningKey),
+    P256(P256SigningKey),
+    P384(p384::ecdsa::SigningKey),
+    P521(p521::ecdsa::SigningKey),
+}
+
+// Only output the key type in order not to leak secrets.
+impl fmt::Debug for SigningKeyInstance {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variant = match self {
+            Self::Symmetric(_) => "Symmetric",
+            Self::Rsa(_) => "Rsa",
+            Self::Ed25519(_) => "Ed25519",
+            Self::K256(_) => "K256",
+            Self::P256(_) => "P256",
+            Self::P384(_) => "P384",
+            Self::P521(_) => "P521",
+        };
+        formatter
+            .debug_tuple("SigningKeyInstance")
+            .field(&variant)
+            .finish()
+    }
+}
+
+impl SigningKeyInstance {
+    /// All supported signing algorithms.
+    pub const ALGORITHMS: [&'static str; 14] = [
+        "HS256", "HS384", "HS512", "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "EdDSA",
+        "ES256K", "ES256", "ES384", "ES512",
+    ];
+
+    /// # Errors
+    ///
+    /// Returns an error if `jwk` is incorrect, not supported, or does not contain
+    /// a private key.
+    pub fn new(jwk: &JsonWebKey<'_>) -> Result<Self, JwkError> {
+        match jwk {
+            JsonWebKey::Symmetric { secret } => {
+                let secret = SecretBytes::owned(secret.to_vec());
+                Ok(Self::Symmetric(secret))
+            }
+            JsonWebKey::Rsa { .. } => RsaPrivateKey::try_from(jwk).map(|key| Self::Rsa(key.into())),
+            JsonWebKey::KeyPair { .. } => Ed25519SecretKey::try_from(jwk).map(Self::Ed25519),
+            JsonWebKey::EllipticCurve { curve, .. } => match curve.as_ref() {
+                "P-256" => P256SigningKey::try_from(jwk).map(Self::P256),
+                "P-384" => Es384::signing_key_from_jwk(jwk).map(Self::P384),
+                "P-521" => Es512::signing_key_from_jwk(jwk).map(Self::P521),
+                _ => K256SigningKey::try_from(jwk).map(Self::K256),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Generates a random key pair of the specified type.
+    pub fn generate(key_type: KeyPairType) -> Self {
+        let rsa_bits = match key_type {
+            KeyPairType::Ed25519 => {
+                let mut seed = [0_u8; Ed25519Seed::BYTES];
+                getrandom(&mut seed).expect_throw("cannot access CSPRNG");
+                let keypair = Ed25519KeyPair::from_seed(Ed25519Seed::new(seed));
+                return Self::Ed25519(keypair.sk);
+            }
+            KeyPairType::Secp256k1 => return Self::K256(K256SigningKey::random(&mut Csprng)),
+            KeyPairType::P256 => return Self::P256(P256SigningKey::random(&mut Csprng)),
+            KeyPairType::P384 => return Self::P384(p384::ecdsa::SigningKey::random(&mut Csprng)),
+            KeyPairType::P521 => return Self::P521(p521::ecdsa::SigningKey::random(&mut Csprng)),
+            KeyPairType::Rsa2048 => 2_048,
+            KeyPairType::Rsa3072 => 3_072,
+            KeyPairType::Rsa4096 => 4_096,
+        };
+        let key = RsaPrivateKey::new(&mut Csprng, rsa_bits).expect_throw("cannot generate RSA key");
+        Self::Rsa(Box::new(key))
+    }
+
+    /// Generates a random key for the specified signing algorithm.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alg` is not one of [`Self::ALGORITHMS`].
+    pub fn random(alg: &str) -> Self {
+        let key_type = match alg {
+            "HS256" | "HS384" | "HS512" => {
+                let mut bytes = vec![0_u8; 64];
+                getrandom(&mut bytes).expect_throw("cannot access CSPRNG");
+                return Self::Symmetric(SecretBytes::owned(bytes));
+            }
+            "EdDSA" => KeyPairType::Ed25519,
+            "ES256K" => KeyPairType::Secp256k1,
+            "ES256" => KeyPairType::P256,
+            "ES384" => KeyPairType::P384,
+            "ES512" => KeyPairType::P521,
+            _ if alg.parse::<Rsa>().is_ok() => KeyPairType::Rsa2048,
+            _ => panic!("unsupported algorithm: {alg}"),
+        };
+        Self::generate(key_type)
+    }
+
+    /// Creates a token with random claims signed with this key.
+    pub fn random_token(&self, alg: &str) -> String {
+        let header = GenericHeader::default()
             .with_token_type("JWT")
             .with_key_id(Self::random_uuid().to_string());
 
@@ -101,8 +427,7 @@ impl KeyInstance {
             Duration::try_hours(1).unwrap_throw(),
         );
 
-        Hs256
-            .token(&header, &claims, key)
+        self.sign_token(alg, &header, &claims)
             .expect_throw("cannot create token")
     }
 
@@ -118,4 +443,324 @@ impl KeyInstance {
             .with_version(uuid::Version::Random)
             .into_uuid()
     }
+
+    /// Returns the JWK presentation of this key, which includes private parts.
+    pub fn to_jwk(&self) -> JsonWebKey<'_> {
+        match self {
+            Self::Symmetric(secret) => JsonWebKey::Symmetric {
+                secret: SecretBytes::borrowed(secret),
+            },
+            Self::Rsa(key) => key.as_ref().into(),
+            Self::Ed25519(key) => key.into(),
+            Self::K256(key) => key.into(),
+            Self::P256(key) => key.into(),
+            Self::P384(key) => Es384::signing_key_to_jwk(key),
+            Self::P521(key) => Es512::signing_key_to_jwk(key),
+        }
+    }
+
+    /// Returns the default signing algorithm for this key.
+    pub fn default_algorithm(&self) -> &'static str {
+        match self {
+            Self::Symmetric(_) => "HS256",
+            Self::Rsa(_) => "RS256",
+            Self::Ed25519(_) => "EdDSA",
+            Self::K256(_) => "ES256K",
+            Self::P256(_) => "ES256",
+            Self::P384(_) => "ES384",
+            Self::P521(_) => "ES512",
+        }
+    }
+
+    /// Returns the verifying key corresponding to this signing key.
+    pub fn to_verifying_key(&self) -> KeyInstance {
+        match self {
+            Self::Symmetric(secret) => KeyInstance::Symmetric(SecretBytes::owned(secret.to_vec())),
+            Self::Rsa(key) => KeyInstance::Rsa(key.to_public_key()),
+            Self::Ed25519(key) => KeyInstance::Ed25519(key.public_key()),
+            Self::K256(key) => KeyInstance::K256(*key.verifying_key()),
+            Self::P256(key) => KeyInstance::P256(*key.verifying_key()),
+            Self::P384(key) => KeyInstance::P384(key.into()),
+            Self::P521(key) => KeyInstance::P521(key.into()),
+        }
+    }
+
+    /// Creates a token with the specified algorithm, header and claims.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the algorithm is not compatible with this key, or if the token
+    /// cannot be created.
+    pub fn sign_token(
+        &self,
+        alg: &str,
+        header: &GenericHeader,
+        claims: &GenericClaims,
+    ) -> Result<String, SigningError> {
+        let mismatch = |expected: &str| SigningError::AlgorithmMismatch {
+            expected: expected.to_owned(),
+            actual: alg.to_owned(),
+        };
+
+        let token = match (self, alg) {
+            (Self::Symmetric(secret), "HS256") => {
+                Hs256.token(header, claims, &Hs256Key::new(secret))
+            }
+            (Self::Symmetric(secret), "HS384") => {
+                Hs384.token(header, claims, &Hs384Key::new(secret))
+            }
+            (Self::Symmetric(secret), "HS512") => {
+                Hs512.token(header, claims, &Hs512Key::new(secret))
+            }
+            (Self::Symmetric(_), _) => return Err(mismatch("HS256, HS384 or HS512")),
+
+            (Self::Rsa(key), _) => alg
+                .parse::<Rsa>()
+                .map_err(|_| mismatch("RS* or PS* algorithm"))?
+                .token(header, claims, key),
+
+            (Self::Ed25519(key), "EdDSA") => Ed25519.token(header, claims, key),
+            (Self::K256(key), "ES256K") => Es256k::<Sha256>::default().token(header, claims, key),
+            (Self::P256(key), "ES256") => Es256.token(header, claims, key),
+            (Self::P384(key), "ES384") => Es384.token(header, claims, key),
+            (Self::P521(key), "ES512") => Es512.token(header, claims, key),
+            _ => return Err(mismatch(self.default_algorithm())),
+        };
+        token.map_err(SigningError::from)
+    }
+}
+
+/// Verifying key from a [`KeySet`] together with its metadata.
+#[derive(Debug)]
+pub struct KeySetEntry {
+    /// Zero-based index of the key in the original set. May differ from the index in [`KeySet`]
+    /// if some keys in the original set are invalid.
+    pub index: usize,
+    /// Key ID (the `kid` field of the JWK).
+    pub key_id: Option<String>,
+    /// SHA-256 key thumbprint encoded in base64url.
+    pub thumbprint: String,
+    pub key: KeyInstance,
+    /// Private key corresponding to `key`, if known. Used to decrypt encrypted tokens.
+    pub private_key: Option<SigningKeyInstance>,
+    /// Thumbprints of the X.509 certificate for the key, if known (e.g., from the `x5c` field
+    /// of the JWK).
+    pub certificate: Option<CertificateThumbprints>,
+}
+
+impl KeySetEntry {
+    /// Creates an entry without a private key. Symmetric keys are an exception; they are
+    /// always usable as private keys.
+    pub fn new(index: usize, key_id: Option<String>, key: KeyInstance) -> Self {
+        let private_key = if let KeyInstance::Symmetric(secret) = &key {
+            Some(SigningKeyInstance::Symmetric(secret.clone()))
+        } else {
+            None
+        };
+        Self {
+            index,
+            key_id,
+            thumbprint: key.thumbprint(),
+            key,
+            private_key,
+            certificate: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_private_key(mut self, private_key: SigningKeyInstance) -> Self {
+        self.private_key = Some(private_key);
+        self
+    }
+
+    #[must_use]
+    pub fn with_certificate(mut self, thumbprints: CertificateThumbprints) -> Self {
+        self.certificate = Some(thumbprints);
+        self
+    }
+
+    fn matches_key_id(&self, key_id: &str) -> bool {
+        self.key_id.as_deref() == Some(key_id) || self.thumbprint == key_id
+    }
+}
+
+impl fmt::Display for KeySetEntry {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "key #{}", self.index + 1)?;
+        if let Some(key_id) = &self.key_id {
+            write!(formatter, " (kid: {key_id})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Signature together with the data it covers. Used for signatures that cannot be verified
+/// by `jwt-compact` directly, such as signatures of nested tokens.
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureInput<'a> {
+    pub algorithm: &'a str,
+    pub key_id: Option<&'a str>,
+    pub signed_data: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+/// Non-empty set of verifying keys, e.g., obtained from a JSON web key set.
+#[derive(Debug)]
+pub struct KeySet {
+    entries: Vec<KeySetEntry>,
+}
+
+impl From<KeyInstance> for KeySet {
+    fn from(key: KeyInstance) -> Self {
+        Self {
+            entries: vec![KeySetEntry::new(0, None, key)],
+        }
+    }
+}
+
+impl KeySet {
+    /// Creates a key set from the provided entries. Returns `None` if `entries` are empty.
+    pub fn new(entries: Vec<KeySetEntry>) -> Option<Self> {
+        if entries.is_empty() {
+            None
+        } else {
+            Some(Self { entries })
+        }
+    }
+
+    pub fn entries(&self) -> &[KeySetEntry] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<KeySetEntry> {
+        self.entries
+    }
+
+    /// Verifies the token using keys in this set.
+    ///
+    /// If the token specifies a key ID (`kid`) matching a key in the set (either by the key ID
+    /// or the key thumbprint), only this key is used. Otherwise, all keys compatible with
+    /// the token algorithm are tried in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is not valid. If several keys were tried,
+    /// the error produced by the last key is returned.
+    pub fn verify_token(
+        &self,
+        token: &UntrustedToken<'_>,
+    ) -> Result<(GenericToken, &KeySetEntry), ValidationError> {
+        let alg = token.algorithm();
+        self.try_keys(
+            token.header().key_id.as_deref(),
+            |entry| entry.key.is_compatible(alg),
+            |entry| entry.key.verify_token(token),
+        )
+    }
+
+    /// Checks whether this set has a key that can be tried to verify a signature: either a key
+    /// with the matching key ID, or a key compatible with the signing algorithm.
+    pub fn has_matching_key(&self, input: SignatureInput<'_>) -> bool {
+        let matches_key_id = input.key_id.is_some_and(|key_id| {
+            self.entries
+                .iter()
+                .any(|entry| entry.matches_key_id(key_id))
+        });
+        matches_key_id
+            || self
+                .entries
+                .iter()
+                .any(|entry| entry.key.is_compatible(input.algorithm))
+    }
+
+    /// Verifies a signature not bound to a JWT with JSON claims (e.g., the signature of a nested
+    /// token) using keys in this set. Keys are selected
+    /// in the same way as for [verification](Self::verify_token()).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is not valid. If several keys were tried,
+    /// the error produced by the last key is returned.
+    pub fn verify_signature(
+        &self,
+        input: SignatureInput<'_>,
+    ) -> Result<&KeySetEntry, ValidationError> {
+        let alg = input.algorithm;
+        let ((), entry) = self.try_keys(
+            input.key_id,
+            |entry| entry.key.is_compatible(alg),
+            |entry| {
+                entry
+                    .key
+                    .verify_signature(alg, input.signed_data, input.signature)
+            },
+        )?;
+        Ok(entry)
+    }
+
+    /// Decrypts the token using private keys in this set. Keys are selected in the same way
+    /// as for [verification](Self::verify_token()).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token cannot be decrypted. If several keys were tried,
+    /// the error produced by the last key is returned.
+    pub fn decrypt_token(
+        &self,
+        token: &EncryptedToken,
+    ) -> Result<(Vec<u8>, &KeySetEntry), DecryptionError> {
+        self.try_keys(
+            token.header().key_id.as_deref(),
+            |entry| {
+                entry
+                    .private_key
+                    .as_ref()
+                    .is_some_and(|key| token.is_compatible(key))
+            },
+            |entry| {
+                let key = entry
+                    .private_key
+                    .as_ref()
+                    .ok_or(DecryptionError::NoPrivateKey)?;
+                token.decrypt(key)
+            },
+        )
+    }
+
+    fn try_keys<T, E>(
+        &self,
+        key_id: Option<&str>,
+        is_compatible: impl Fn(&KeySetEntry) -> bool,
+        action: impl Fn(&KeySetEntry) -> Result<T, E>,
+    ) -> Result<(T, &KeySetEntry), E> {
+        if let Some(key_id) = key_id {
+            let matching_entry = self
+                .entries
+                .iter()
+                .find(|entry| entry.matches_key_id(key_id));
+            if let Some(entry) = matching_entry {
+                return action(entry).map(|output| (output, entry));
+            }
+        }
+
+        let mut compatible_entries = self
+            .entries
+            .iter()
+            .filter(|entry| is_compatible(entry))
+            .peekable();
+        if compatible_entries.peek().is_none() {
+            // Use the first key to get an informative error.
+            let entry = &self.entries[0];
+            return action(entry).map(|output| (output, entry));
+        }
+
+        let mut last_err = None;
+        for entry in compatible_entries {
+            match action(entry) {
+                Ok(output) => return Ok((output, entry)),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_throw())
+    }
 }
@@ -14,12 +14,20 @@ use wasm_bindgen::prelude::*;
 use yew::{AppHandle, Renderer};
 
 // Modules are public for the sake of integration testing.
+pub mod alg;
 pub mod components;
-mod fields;
+pub mod detached;
+pub mod expectations;
+pub mod fields;
+pub mod jwe;
+pub mod jws;
 pub mod keys;
+pub mod nested;
+mod pem;
 mod rng;
+pub mod x509;
 
-use crate::components::{App, AppMessage, AppProperties};
+use crate::components::{claims_dictionary::ClaimsDictionary, App, AppMessage, AppProperties};
 
 #[wasm_bindgen]
 #[derive(Debug)]
@@ -51,3 +59,14 @@ pub fn run_app(save: bool) -> AppLink {
     let app = Renderer::<App>::with_root_and_props(element, AppProperties { save }).render();
     AppLink { inner: app }
 }
+
+#[wasm_bindgen(js_name = runClaimsDictionary)]
+pub fn run_claims_dictionary() {
+    let window = web_sys::window().expect_throw("no Window");
+    let document = window.document().expect_throw("no Document");
+    let element = document
+        .query_selector("#claims-root")
+        .expect_throw("cannot get claims dictionary root node")
+        .expect_throw("cannot unwrap claims dictionary root node");
+    Renderer::<ClaimsDictionary>::with_root(element).render();
+}
@@ -0,0 +1,116 @@
+//! Signed tokens wrapping another token (nested JWTs with `cty: JWT`, RFC 7519, section 5.2).
+//!
+//! `jwt-compact` only accepts JSON (or CBOR) payloads, so such tokens are parsed separately;
+//! their signatures are verified via [`KeySet::verify_signature()`].
+//!
+//! [`KeySet::verify_signature()`]: crate::keys::KeySet::verify_signature()
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use jwt_compact::{Header, ParseError};
+
+use crate::keys::SignatureInput;
+
+/// Checks whether the content type (`cty`) signals that the payload is a nested token.
+/// As per RFC 7519, the comparison is case-insensitive.
+pub fn is_nested_content_type(content_type: &str) -> bool {
+    content_type.eq_ignore_ascii_case("JWT")
+}
+
+/// Signed token with another token as its payload.
+#[derive(Debug, Clone)]
+pub struct NestingToken {
+    algorithm: String,
+    content_type: String,
+    critical: Vec<String>,
+    header: Header,
+    signed_data: String,
+    payload: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl NestingToken {
+    /// Parses a token from the compact serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is malformed, or if its content type does not signal
+    /// a nested token.
+    pub fn new(raw: &str) -> Result<Self, ParseError> {
+        let raw = raw.trim();
+        let segments: Vec<_> = raw.split('.').collect();
+        let [encoded_header, encoded_payload, encoded_signature] = segments[..] else {
+            return Err(ParseError::InvalidTokenStructure);
+        };
+        let decode = |segment: &str| {
+            Base64UrlUnpadded::decode_vec(segment).map_err(|_| ParseError::InvalidBase64Encoding)
+        };
+
+        let raw_header = decode(encoded_header)?;
+        let mut fields: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(&raw_header).map_err(ParseError::MalformedHeader)?;
+        let mut take_str = |name: &str| -> Result<String, ParseError> {
+            let value = fields.remove(name).unwrap_or_default();
+            serde_json::from_value(value).map_err(ParseError::MalformedHeader)
+        };
+        let algorithm = take_str("alg")?;
+        let content_type = take_str("cty")?;
+        if !is_nested_content_type(&content_type) {
+            return Err(ParseError::UnsupportedContentType(content_type));
+        }
+        let critical: Vec<String> = fields
+            .remove("crit")
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(ParseError::MalformedHeader)?
+            .unwrap_or_default();
+        let header = serde_json::from_value(fields.into()).map_err(ParseError::MalformedHeader)?;
+
+        let signed_data_len = encoded_header.len() + 1 + encoded_payload.len();
+        Ok(Self {
+            algorithm,
+            content_type,
+            critical,
+            header,
+            signed_data: raw[..signed_data_len].to_owned(),
+            payload: decode(encoded_payload)?,
+            signature: decode(encoded_signature)?,
+        })
+    }
+
+    pub fn algorithm(&self) -> &str {
+        &self.algorithm
+    }
+
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Returns names of critical header fields (`crit`).
+    pub fn critical_fields(&self) -> &[String] {
+        &self.critical
+    }
+
+    /// Returns the input for signature verification.
+    pub fn signature_input(&self) -> SignatureInput<'_> {
+        SignatureInput {
+            algorithm: &self.algorithm,
+            key_id: self.header.key_id.as_deref(),
+            signed_data: self.signed_data.as_bytes(),
+            signature: &self.signature,
+        }
+    }
+
+    /// Returns the payload, which should be a nested token in the compact serialization.
+    /// The payload is not authenticated until the signature is verified.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn signature_bytes(&self) -> &[u8] {
+        &self.signature
+    }
+}
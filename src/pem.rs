@@ -0,0 +1,188 @@
+//! Parsing PEM-encoded public keys into equivalent JSON web keys.
+
+use jwt_compact::jwk::{JsonWebKey, JwkError};
+use k256::ecdsa::VerifyingKey as K256PublicKey;
+use p256::ecdsa::VerifyingKey as P256PublicKey;
+use spki::{
+    der::{self, Decode},
+    ObjectIdentifier, SubjectPublicKeyInfoRef,
+};
+
+use std::{borrow::Cow, fmt};
+
+use crate::alg::{P384PublicKey, P521PublicKey};
+
+/// OID for RSA keys (`rsaEncryption`).
+const RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+/// OID for Ed25519 keys.
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+/// OID for elliptic curve keys (`id-ecPublicKey`). The curve is specified
+/// in the algorithm parameters.
+const EC_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+
+const SECP256K1_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.10");
+const P256_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const P384_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+const P521_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.35");
+
+/// Format of a PEM-encoded public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PemFormat {
+    /// `SubjectPublicKeyInfo` structure from X.509 (`BEGIN PUBLIC KEY` label).
+    Spki,
+    /// `RSAPublicKey` structure from PKCS #1 (`BEGIN RSA PUBLIC KEY` label).
+    Pkcs1,
+}
+
+impl fmt::Display for PemFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Spki => "PEM (SubjectPublicKeyInfo)",
+            Self::Pkcs1 => "PEM (PKCS #1)",
+        })
+    }
+}
+
+/// Errors that can occur when parsing a PEM-encoded public key.
+#[derive(Debug)]
+pub enum PemError {
+    /// Error decoding PEM encapsulation.
+    Pem(pem_rfc7468::Error),
+    /// PEM label is not supported.
+    UnsupportedLabel(String),
+    /// Error decoding DER-encoded key.
+    Der(spki::Error),
+    /// Key algorithm is not supported.
+    UnsupportedAlgorithm(ObjectIdentifier),
+    /// Elliptic curve is not supported.
+    UnsupportedCurve(ObjectIdentifier),
+    /// Key is malformed.
+    Key(JwkError),
+}
+
+impl fmt::Display for PemError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pem(err) => write!(formatter, "cannot decode PEM: {err}"),
+            Self::UnsupportedLabel(label) => write!(
+                formatter,
+                "unsupported PEM label `{label}`; expected `PUBLIC KEY` or `RSA PUBLIC KEY`"
+            ),
+            Self::Der(err) => write!(formatter, "cannot decode key: {err}"),
+            Self::UnsupportedAlgorithm(oid) => {
+                write!(formatter, "unsupported key algorithm (OID: {oid})")
+            }
+            Self::UnsupportedCurve(oid) => {
+                write!(formatter, "unsupported elliptic curve (OID: {oid})")
+            }
+            Self::Key(err) => write!(formatter, "invalid key: {err}"),
+        }
+    }
+}
+
+impl PemError {
+    fn der(err: der::Error) -> Self {
+        Self::Der(err.into())
+    }
+}
+
+/// Checks whether the provided string looks like a PEM-encoded value.
+pub fn is_pem(raw: &str) -> bool {
+    raw.trim_start().starts_with("-----BEGIN ")
+}
+
+/// Parses a PEM-encoded public key into an equivalent JWK.
+pub fn parse_public_key(raw: &str) -> Result<(JsonWebKey<'static>, PemFormat), PemError> {
+    let (label, der_bytes) =
+        pem_rfc7468::decode_vec(raw.trim().as_bytes()).map_err(PemError::Pem)?;
+    match label {
+        "PUBLIC KEY" => {
+            let jwk = parse_spki(&der_bytes)?;
+            Ok((jwk, PemFormat::Spki))
+        }
+        "RSA PUBLIC KEY" => {
+            let jwk = parse_pkcs1(&der_bytes)?;
+            Ok((jwk, PemFormat::Pkcs1))
+        }
+        _ => Err(PemError::UnsupportedLabel(label.to_owned())),
+    }
+}
+
+fn parse_pkcs1(der_bytes: &[u8]) -> Result<JsonWebKey<'static>, PemError> {
+    let key = pkcs1::RsaPublicKey::from_der(der_bytes).map_err(PemError::der)?;
+    Ok(JsonWebKey::Rsa {
+        modulus: Cow::Owned(key.modulus.as_bytes().to_vec()),
+        public_exponent: Cow::Owned(key.public_exponent.as_bytes().to_vec()),
+        private_parts: None,
+    })
+}
+
+pub(crate) fn parse_spki(der_bytes: &[u8]) -> Result<JsonWebKey<'static>, PemError> {
+    let spki = SubjectPublicKeyInfoRef::from_der(der_bytes).map_err(PemError::der)?;
+    let key_bytes = spki
+        .subject_public_key
+        .as_bytes()
+        .ok_or_else(|| PemError::der(der::Tag::BitString.value_error()))?;
+
+    match spki.algorithm.oid {
+        RSA_OID => parse_pkcs1(key_bytes),
+        ED25519_OID => Ok(JsonWebKey::KeyPair {
+            curve: Cow::Borrowed("Ed25519"),
+            x: Cow::Owned(key_bytes.to_vec()),
+            secret: None,
+        }),
+        EC_OID => {
+            let curve_oid = spki.algorithm.parameters_oid().map_err(PemError::Der)?;
+            ec_key_to_jwk(curve_oid, key_bytes)
+        }
+        oid => Err(PemError::UnsupportedAlgorithm(oid)),
+    }
+}
+
+/// Converts a SEC1-encoded elliptic curve point into a JWK. This performs point decompression
+/// if necessary.
+fn ec_key_to_jwk(
+    curve_oid: ObjectIdentifier,
+    point: &[u8],
+) -> Result<JsonWebKey<'static>, PemError> {
+    let jwk = match curve_oid {
+        SECP256K1_OID => {
+            let key = K256PublicKey::from_sec1_bytes(point).map_err(invalid_point)?;
+            let point = key.to_encoded_point(false);
+            uncompressed_ec_jwk("secp256k1", point.x(), point.y())
+        }
+        P256_OID => {
+            let key = P256PublicKey::from_sec1_bytes(point).map_err(invalid_point)?;
+            let point = key.to_encoded_point(false);
+            uncompressed_ec_jwk("P-256", point.x(), point.y())
+        }
+        P384_OID => {
+            let key = P384PublicKey::from_sec1_bytes(point).map_err(PemError::Key)?;
+            JsonWebKey::from(&key)
+        }
+        P521_OID => {
+            let key = P521PublicKey::from_sec1_bytes(point).map_err(PemError::Key)?;
+            JsonWebKey::from(&key)
+        }
+        oid => return Err(PemError::UnsupportedCurve(oid)),
+    };
+    Ok(jwk)
+}
+
+#[allow(clippy::needless_pass_by_value)] // more convenient to use in `map_err()`
+fn invalid_point(err: k256::ecdsa::Error) -> PemError {
+    PemError::Key(JwkError::custom(anyhow::anyhow!(err)))
+}
+
+fn uncompressed_ec_jwk<B: AsRef<[u8]>>(
+    curve: &'static str,
+    x: Option<B>,
+    y: Option<B>,
+) -> JsonWebKey<'static> {
+    JsonWebKey::EllipticCurve {
+        curve: Cow::Borrowed(curve),
+        x: Cow::Owned(x.expect("x coordinate").as_ref().to_vec()),
+        y: Cow::Owned(y.expect("y coordinate").as_ref().to_vec()),
+        secret: None,
+    }
+}
@@ -6,6 +6,9 @@
 use getrandom::{register_custom_getrandom, Error};
 use wasm_bindgen::prelude::*;
 
+/// CSPRNG backed by the host JS CSPRNG (via `getrandom`). Should be used for key generation.
+pub use rand_core::OsRng as Csprng;
+
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_name = getRandomValues, js_namespace = crypto)]
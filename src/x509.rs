@@ -0,0 +1,424 @@
+//! Parsing and validation of X.509 certificate chains embedded into the token header
+//! (`x5c`, RFC 7515, section 4.1.6).
+//!
+//! Validation is performed fully offline: the chain is checked for internal consistency,
+//! and optionally against a root certificate supplied by the user. Revocation is not checked.
+
+use base64ct::{Base64, Base64UrlUnpadded, Encoding};
+use chrono::{DateTime, Utc};
+use jwt_compact::jwk::JsonWebKey;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use spki::{
+    der::{self, asn1::UintRef, Decode, Encode, Reader, SliceReader},
+    ObjectIdentifier,
+};
+use x509_cert::ext::pkix::BasicConstraints;
+
+use std::fmt;
+
+use crate::{
+    keys::KeyInstance,
+    pem::{self, PemError},
+};
+
+const SHA256_WITH_RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const SHA384_WITH_RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.12");
+const SHA512_WITH_RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.13");
+const ECDSA_WITH_SHA256_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+const ECDSA_WITH_SHA384_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+const ECDSA_WITH_SHA512_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.4");
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+/// Checks whether the provided string looks like a PEM-encoded certificate.
+pub fn is_pem_certificate(raw: &str) -> bool {
+    raw.trim_start().starts_with("-----BEGIN CERTIFICATE-----")
+}
+
+/// Thumbprints of a DER-encoded X.509 certificate, encoded in base64url. These are compared
+/// with the `x5t` and `x5t#S256` token headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateThumbprints {
+    /// SHA-1 thumbprint (cf. `x5t` header).
+    pub sha1: String,
+    /// SHA-256 thumbprint (cf. `x5t#S256` header).
+    pub sha256: String,
+}
+
+/// Errors that can occur when parsing X.509 certificates.
+#[derive(Debug)]
+pub enum CertificateError {
+    /// `x5c` header is not a non-empty array of strings.
+    MalformedChain,
+    /// Certificate in the chain is not valid base64.
+    Base64 { index: usize },
+    /// Error decoding DER-encoded certificate.
+    Der { index: usize, err: der::Error },
+    /// Error decoding PEM encapsulation.
+    Pem(pem_rfc7468::Error),
+    /// PEM label is not supported.
+    UnsupportedLabel(String),
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedChain => {
+                formatter.write_str("certificate chain must be a non-empty array of strings")
+            }
+            Self::Base64 { index } => {
+                write!(formatter, "certificate #{} is not valid base64", index + 1)
+            }
+            Self::Der { index, err } => {
+                write!(formatter, "cannot decode certificate #{}: {err}", index + 1)
+            }
+            Self::Pem(err) => write!(formatter, "cannot decode PEM: {err}"),
+            Self::UnsupportedLabel(label) => write!(
+                formatter,
+                "unsupported PEM label `{label}`; expected `CERTIFICATE`"
+            ),
+        }
+    }
+}
+
+/// Errors that can occur when validating a [`CertificateChain`].
+#[derive(Debug)]
+pub enum ChainValidationError {
+    /// Certificate is not valid yet.
+    NotYetValid { index: usize },
+    /// Certificate has expired.
+    Expired { index: usize },
+    /// Certificate issuer does not match the subject of the next certificate in the chain.
+    IssuerMismatch { index: usize },
+    /// Certificate issuing another certificate is not a CA certificate.
+    NotCa { index: usize },
+    /// Certificate signature cannot be verified.
+    InvalidSignature { index: usize, err: String },
+    /// Root certificate is not valid at the evaluation time.
+    InvalidRoot,
+}
+
+impl fmt::Display for ChainValidationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotYetValid { index } => {
+                write!(formatter, "certificate #{} is not valid yet", index + 1)
+            }
+            Self::Expired { index } => {
+                write!(formatter, "certificate #{} has expired", index + 1)
+            }
+            Self::IssuerMismatch { index } => write!(
+                formatter,
+                "issuer of certificate #{} does not match the subject of the issuing certificate",
+                index + 1
+            ),
+            Self::NotCa { index } => write!(
+                formatter,
+                "issuing certificate of certificate #{} is not a CA certificate",
+                index + 1
+            ),
+            Self::InvalidSignature { index, err } => write!(
+                formatter,
+                "signature of certificate #{} cannot be verified: {err}",
+                index + 1
+            ),
+            Self::InvalidRoot => {
+                formatter.write_str("root certificate is not valid at the evaluation time")
+            }
+        }
+    }
+}
+
+/// Parsed X.509 certificate.
+#[derive(Debug)]
+pub struct Certificate {
+    der: Vec<u8>,
+    inner: x509_cert::Certificate,
+    public_key: Result<JsonWebKey<'static>, PemError>,
+}
+
+impl Certificate {
+    fn from_der(der: Vec<u8>) -> Result<Self, der::Error> {
+        let inner = x509_cert::Certificate::from_der(&der)?;
+        let spki = inner.tbs_certificate.subject_public_key_info.to_der()?;
+        let public_key = pem::parse_spki(&spki);
+        Ok(Self {
+            der,
+            inner,
+            public_key,
+        })
+    }
+
+    /// Parses a PEM-encoded certificate (`BEGIN CERTIFICATE` label).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the certificate cannot be parsed.
+    pub fn from_pem(raw: &str) -> Result<Self, CertificateError> {
+        let (label, der) =
+            pem_rfc7468::decode_vec(raw.trim().as_bytes()).map_err(CertificateError::Pem)?;
+        if label != "CERTIFICATE" {
+            return Err(CertificateError::UnsupportedLabel(label.to_owned()));
+        }
+        Self::from_der(der).map_err(|err| CertificateError::Der { index: 0, err })
+    }
+
+    /// Returns the subject name as per RFC 4514.
+    pub fn subject(&self) -> String {
+        self.inner.tbs_certificate.subject.to_string()
+    }
+
+    /// Returns the issuer name as per RFC 4514.
+    pub fn issuer(&self) -> String {
+        self.inner.tbs_certificate.issuer.to_string()
+    }
+
+    pub fn not_before(&self) -> DateTime<Utc> {
+        Self::convert_time(self.inner.tbs_certificate.validity.not_before)
+    }
+
+    pub fn not_after(&self) -> DateTime<Utc> {
+        Self::convert_time(self.inner.tbs_certificate.validity.not_after)
+    }
+
+    fn convert_time(time: x509_cert::time::Time) -> DateTime<Utc> {
+        let timestamp = i64::try_from(time.to_unix_duration().as_secs()).unwrap_or(i64::MAX);
+        DateTime::from_timestamp(timestamp, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    /// Returns the subject public key as a JWK.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key type is not supported.
+    pub fn public_key(&self) -> Result<&JsonWebKey<'static>, &PemError> {
+        self.public_key.as_ref()
+    }
+
+    /// Converts this certificate into the subject public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key type is not supported.
+    pub fn into_public_key(self) -> Result<JsonWebKey<'static>, PemError> {
+        self.public_key
+    }
+
+    /// Returns SHA-1 and SHA-256 thumbprints of the DER-encoded certificate.
+    pub fn thumbprints(&self) -> CertificateThumbprints {
+        CertificateThumbprints {
+            sha1: Base64UrlUnpadded::encode_string(&Sha1::digest(&self.der)),
+            sha256: Base64UrlUnpadded::encode_string(&Sha256::digest(&self.der)),
+        }
+    }
+
+    fn check_validity(&self, index: usize, now: DateTime<Utc>) -> Result<(), ChainValidationError> {
+        if now < self.not_before() {
+            Err(ChainValidationError::NotYetValid { index })
+        } else if now > self.not_after() {
+            Err(ChainValidationError::Expired { index })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks whether the certificate is allowed to issue other certificates
+    /// (the `basicConstraints` extension with the `cA` flag set).
+    fn is_ca(&self) -> bool {
+        matches!(
+            self.inner.tbs_certificate.get::<BasicConstraints>(),
+            Ok(Some((_, BasicConstraints { ca: true, .. })))
+        )
+    }
+
+    /// Checks that this certificate is issued by `issuer`.
+    fn check_issuer(&self, index: usize, issuer: &Self) -> Result<(), ChainValidationError> {
+        if self.inner.tbs_certificate.issuer != issuer.inner.tbs_certificate.subject {
+            return Err(ChainValidationError::IssuerMismatch { index });
+        }
+        if !issuer.is_ca() {
+            return Err(ChainValidationError::NotCa { index });
+        }
+        self.verify_signature(issuer)
+            .map_err(|err| ChainValidationError::InvalidSignature { index, err })
+    }
+
+    fn verify_signature(&self, issuer: &Self) -> Result<(), String> {
+        // RFC 5280, section 4.1.1.2: the algorithm must match the one in the signed data.
+        if self.inner.signature_algorithm != self.inner.tbs_certificate.signature {
+            return Err(
+                "signature algorithm does not match the algorithm in the signed certificate data"
+                    .to_owned(),
+            );
+        }
+
+        let issuer_key = issuer.public_key.as_ref().map_err(ToString::to_string)?;
+        let issuer_key = KeyInstance::new(issuer_key).map_err(|err| err.to_string())?;
+        let signature_oid = self.inner.signature_algorithm.oid;
+        let alg = match (signature_oid, &issuer_key) {
+            (SHA256_WITH_RSA_OID, KeyInstance::Rsa(_)) => "RS256",
+            (SHA384_WITH_RSA_OID, KeyInstance::Rsa(_)) => "RS384",
+            (SHA512_WITH_RSA_OID, KeyInstance::Rsa(_)) => "RS512",
+            (ECDSA_WITH_SHA256_OID, KeyInstance::K256(_)) => "ES256K",
+            (ECDSA_WITH_SHA256_OID, KeyInstance::P256(_)) => "ES256",
+            (ECDSA_WITH_SHA384_OID, KeyInstance::P384(_)) => "ES384",
+            (ECDSA_WITH_SHA512_OID, KeyInstance::P521(_)) => "ES512",
+            (ED25519_OID, KeyInstance::Ed25519(_)) => "EdDSA",
+            (oid, key) => return Err(unsupported_signature_message(oid, key)),
+        };
+
+        let signature = self
+            .inner
+            .signature
+            .as_bytes()
+            .ok_or("signature has unused bits")?;
+        let signature = match alg {
+            "ES256" | "ES256K" => ecdsa_signature_to_fixed(signature, 32),
+            "ES384" => ecdsa_signature_to_fixed(signature, 48),
+            "ES512" => ecdsa_signature_to_fixed(signature, 66),
+            _ => Ok(signature.to_vec()),
+        }
+        .map_err(|err| format!("malformed signature: {err}"))?;
+        let signed_data = self
+            .inner
+            .tbs_certificate
+            .to_der()
+            .map_err(|err| err.to_string())?;
+        issuer_key
+            .verify_signature(alg, &signed_data, &signature)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Explains why a certificate signature with the specified algorithm cannot be verified
+/// with the issuer key.
+fn unsupported_signature_message(oid: ObjectIdentifier, issuer_key: &KeyInstance) -> String {
+    let hash = match oid {
+        ECDSA_WITH_SHA256_OID => Some("SHA-256"),
+        ECDSA_WITH_SHA384_OID => Some("SHA-384"),
+        ECDSA_WITH_SHA512_OID => Some("SHA-512"),
+        _ => None,
+    };
+    let curve = match issuer_key {
+        KeyInstance::K256(_) => Some("secp256k1"),
+        KeyInstance::P256(_) => Some("P-256"),
+        KeyInstance::P384(_) => Some("P-384"),
+        KeyInstance::P521(_) => Some("P-521"),
+        _ => None,
+    };
+    let is_known_oid = hash.is_some()
+        || matches!(
+            oid,
+            SHA256_WITH_RSA_OID | SHA384_WITH_RSA_OID | SHA512_WITH_RSA_OID | ED25519_OID
+        );
+
+    match (hash, curve) {
+        (Some(hash), Some(curve)) => {
+            format!("unsupported hash/curve combination: ECDSA with {hash} by a {curve} issuer key")
+        }
+        _ if is_known_oid => {
+            format!("signature algorithm (OID: {oid}) does not match the issuer key type")
+        }
+        _ => format!("unsupported signature algorithm (OID: {oid})"),
+    }
+}
+
+/// Converts a DER-encoded ECDSA signature (`Ecdsa-Sig-Value` from RFC 3279) to the fixed-size
+/// encoding used in JWS.
+fn ecdsa_signature_to_fixed(signature: &[u8], scalar_len: usize) -> Result<Vec<u8>, der::Error> {
+    let mut reader = SliceReader::new(signature)?;
+    let (r, s) = reader.sequence(|reader| {
+        let r = UintRef::decode(reader)?;
+        let s = UintRef::decode(reader)?;
+        Ok((r, s))
+    })?;
+    reader.finish(())?;
+
+    let mut fixed = vec![0_u8; scalar_len * 2];
+    for (scalar, chunk) in [r, s].iter().zip(fixed.chunks_mut(scalar_len)) {
+        let bytes = scalar.as_bytes();
+        if bytes.len() > scalar_len {
+            return Err(der::Tag::Integer.value_error());
+        }
+        chunk[scalar_len - bytes.len()..].copy_from_slice(bytes);
+    }
+    Ok(fixed)
+}
+
+/// Certificate chain from the `x5c` header. The first certificate is the leaf one,
+/// i.e., the one containing the key used to sign the token.
+#[derive(Debug)]
+pub struct CertificateChain {
+    certificates: Vec<Certificate>,
+}
+
+impl CertificateChain {
+    /// Parses the chain from the `x5c` header value, i.e., an array of base64-encoded
+    /// (not base64url!) DER certificates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the header value is malformed.
+    pub fn from_header(value: &serde_json::Value) -> Result<Self, CertificateError> {
+        let items = value.as_array().ok_or(CertificateError::MalformedChain)?;
+        if items.is_empty() {
+            return Err(CertificateError::MalformedChain);
+        }
+        let certificates = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let item = item.as_str().ok_or(CertificateError::MalformedChain)?;
+                let der =
+                    Base64::decode_vec(item).map_err(|_| CertificateError::Base64 { index })?;
+                Certificate::from_der(der).map_err(|err| CertificateError::Der { index, err })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { certificates })
+    }
+
+    pub fn certificates(&self) -> &[Certificate] {
+        &self.certificates
+    }
+
+    /// Returns the leaf certificate.
+    pub fn leaf(&self) -> &Certificate {
+        &self.certificates[0]
+    }
+
+    /// Validates the chain at the specified time. Each certificate must be valid at this time
+    /// and be issued by the next certificate in the chain. If `root` is specified, the last
+    /// certificate in the chain must either coincide with it, or be issued by it.
+    ///
+    /// Without a root certificate, the chain is only checked for internal consistency, which
+    /// proves nothing about the token issuer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first encountered validation error.
+    pub fn validate(
+        &self,
+        root: Option<&Certificate>,
+        now: DateTime<Utc>,
+    ) -> Result<(), ChainValidationError> {
+        for (index, certificate) in self.certificates.iter().enumerate() {
+            certificate.check_validity(index, now)?;
+        }
+        for (index, pair) in self.certificates.windows(2).enumerate() {
+            pair[0].check_issuer(index, &pair[1])?;
+        }
+
+        let Some(root) = root else {
+            return Ok(());
+        };
+        root.check_validity(0, now)
+            .map_err(|_| ChainValidationError::InvalidRoot)?;
+        let last_index = self.certificates.len() - 1;
+        let last = &self.certificates[last_index];
+        if last.der == root.der {
+            Ok(())
+        } else {
+            last.check_issuer(last_index, root)
+        }
+    }
+}
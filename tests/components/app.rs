@@ -2,20 +2,39 @@
 
 // TODO: test token + no key
 
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::{TimeZone, Utc};
 use gloo_timers::future::sleep;
-use jwt_compact::{alg::SecretBytes, UntrustedToken};
+use jwt_compact::{
+    alg::{Hs256, Hs256Key, SecretBytes},
+    jwk::JsonWebKey,
+    AlgorithmExt, Claims, Header, TimeOptions, UntrustedToken,
+};
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
-use web_sys::Element;
+use web_sys::{Element, HtmlTextAreaElement, InputEvent};
 
 use std::{collections::HashMap, time::Duration};
 
 use super::{
-    assert_no_child, extract_main_value, extract_rows, select_single_element, TestRigBase,
-    HS256_KEY, HS256_TOKEN, K256_JWK,
+    assert_no_child, extract_main_value, extract_rows, select_single_element, sign_p256_token,
+    token_with_certificate_chain, token_with_embedded_key, TestRigBase, A128GCM_KEY, A128GCM_TOKEN,
+    CRITICAL_HEADER_TOKEN, DETACHED_TOKEN, DETACHED_TOKEN_PAYLOAD, ECDH_ES_TOKEN, ES256_TOKEN,
+    ES384_TOKEN, ES512_TOKEN, GENERAL_JSON_TOKEN, HS256_KEY, HS256_TOKEN, JSON_TOKEN_KEY, K256_JWK,
+    NESTED_JWE_KEY, NESTED_JWE_TOKEN, NESTED_JWS_INNER_KEY, NESTED_JWS_OUTER_KEY, NESTED_JWS_TOKEN,
+    P256_JWK, P256_PRIVATE_JWK, P384_JWK, P521_JWK, RSA_OAEP_TOKEN, RSA_PRIVATE_JWK,
+    UNENCODED_TOKEN, UNSECURED_TOKEN, X5C_LEAF_SHA1_THUMBPRINT, X5C_LEAF_SHA256_THUMBPRINT,
+    X5C_ROOT_CERTIFICATE,
 };
 use justwebtoken_io::{
-    components::{App, AppMessage, AppProperties},
-    keys::KeyInstance,
+    components::{
+        key_generator::GeneratedKeyPair, token_input::InputToken, App, AppMessage, AppProperties,
+    },
+    detached::DetachedToken,
+    expectations::ExpectationField,
+    jwe::EncryptedToken,
+    keys::{GenericHeader, KeyInstance, KeyPairType, KeySet, KeySetEntry, SigningKeyInstance},
+    x509::CertificateThumbprints,
 };
 
 type TestRig = TestRigBase<App>;
@@ -41,6 +60,21 @@ async fn info_alert_is_displayed_by_default() {
     assert!(alert_text.contains("No key / token"), "{alert_text}");
 }
 
+/// Extracts the UTC timestamp of a time claim together with its validation error (if any).
+fn extract_time_claim(value_column: &Element) -> String {
+    let timestamp = select_single_element(value_column, ".time-claim-utc");
+    let mut text = timestamp.text_content().unwrap();
+    if let Some(badge) = value_column.query_selector(".badge").unwrap() {
+        text += &badge.text_content().unwrap();
+    }
+    text
+}
+
+fn extract_relative_time(value_column: &Element) -> String {
+    let relative = select_single_element(value_column, ".time-claim-relative");
+    relative.text_content().unwrap()
+}
+
 #[wasm_bindgen_test]
 async fn claims_are_displayed_for_correct_token() {
     let rig = TestRig::new(AppProperties::default());
@@ -51,12 +85,227 @@ async fn claims_are_displayed_for_correct_token() {
 
     let claims = get_decoded_claims(&rig.root_element);
     assert_eq!(claims.len(), 3, "{claims:?}");
-    let exp = extract_main_value(&claims["Expiration timestamp"]);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
     assert_eq!(exp, "2011-03-22 18:43:00 UTC token has expired");
+    let exp_details = claims["Expiration timestamp"].text_content().unwrap();
+    assert!(exp_details.contains("1300819380"), "{exp_details}");
+    let relative = extract_relative_time(&claims["Expiration timestamp"]);
+    assert!(
+        relative.starts_with("expired ") && relative.ends_with(" years ago"),
+        "{relative}"
+    );
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+#[wasm_bindgen_test]
+async fn time_claims_are_validated_at_custom_time() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let token = UntrustedToken::new(HS256_TOKEN).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let info = select_single_element(&rig.root_element, "#time-settings-info");
+    let info = info.text_content().unwrap();
+    assert!(info.contains("at the current time"), "{info}");
+    assert!(info.contains("60 s"), "{info}");
+
+    // 30 seconds after the expiration, which is covered by the default leeway.
+    let evaluated_at = Utc.with_ymd_and_hms(2011, 3, 22, 18, 43, 30).unwrap();
+    rig.send_message(AppMessage::SetEvaluationTime(Some(evaluated_at)))
+        .await;
+    let claims = get_decoded_claims(&rig.root_element);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
+    assert_eq!(exp, "2011-03-22 18:43:00 UTC");
+    let relative = extract_relative_time(&claims["Expiration timestamp"]);
+    assert_eq!(relative, "expired 30 seconds ago");
+    let info = select_single_element(&rig.root_element, "#time-settings-info");
+    let info = info.text_content().unwrap();
+    assert!(info.contains("at 2011-03-22 18:43:30 UTC"), "{info}");
+
+    rig.send_message(AppMessage::SetLeeway(chrono::Duration::zero()))
+        .await;
+    let claims = get_decoded_claims(&rig.root_element);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
+    assert_eq!(exp, "2011-03-22 18:43:00 UTC token has expired");
+    let info = select_single_element(&rig.root_element, "#time-settings-info");
+    let info = info.text_content().unwrap();
+    assert!(info.contains("0 s"), "{info}");
+
+    rig.send_message(AppMessage::SetEvaluationTime(None)).await;
+    let claims = get_decoded_claims(&rig.root_element);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
+    assert_eq!(exp, "2011-03-22 18:43:00 UTC token has expired");
+}
+
+#[wasm_bindgen_test]
+async fn time_claims_are_rerendered_once_token_expires() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::SetLeeway(chrono::Duration::zero()))
+        .await;
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let time_options = TimeOptions::default();
+    let claims = Claims::new(serde_json::json!({ "iss": "joe" }))
+        .set_duration(&time_options, chrono::Duration::try_seconds(1).unwrap());
+    let token = Hs256
+        .token(&Header::empty(), &claims, &Hs256Key::new(HS256_KEY))
+        .unwrap();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
+    assert!(exp.ends_with(" UTC"), "{exp}");
+    let relative = extract_relative_time(&claims["Expiration timestamp"]);
+    assert!(relative.starts_with("expires in "), "{relative}");
+
+    sleep(Duration::from_millis(1_500)).await;
+    let claims = get_decoded_claims(&rig.root_element);
+    let exp = extract_time_claim(&claims["Expiration timestamp"]);
+    assert!(exp.ends_with(" UTC token has expired"), "{exp}");
+    let relative = extract_relative_time(&claims["Expiration timestamp"]);
+    assert!(relative.starts_with("expired "), "{relative}");
+}
+
+fn get_expectation_errors(root_element: &Element) -> Vec<String> {
+    let errors = select_single_element(root_element, "#expectation-errors");
+    let errors = errors.query_selector_all("li").unwrap();
+    (0..errors.length())
+        .map(|i| errors.get(i).unwrap().text_content().unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn token_is_checked_against_expectations() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::SetExpectation(
+        ExpectationField::Issuers,
+        "joe, bob".to_owned(),
+    ))
+    .await;
+    rig.send_message(AppMessage::SetExpectation(
+        ExpectationField::RequiredClaims,
+        "exp".to_owned(),
+    ))
+    .await;
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let token = UntrustedToken::new(HS256_TOKEN).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+    assert_no_child(&rig.root_element, "#expectation-errors");
+
+    let expectations = [
+        (ExpectationField::Issuers, "bob"),
+        (ExpectationField::Audience, "https://api.example.com"),
+        (ExpectationField::Subject, "alice"),
+        (ExpectationField::RequiredClaims, "exp, jti"),
+        (ExpectationField::Algorithms, "RS256, ES256"),
+    ];
+    for (field, value) in expectations {
+        rig.send_message(AppMessage::SetExpectation(field, value.to_owned()))
+            .await;
+    }
+
+    let errors = get_expectation_errors(&rig.root_element);
+    assert_eq!(
+        errors,
+        [
+            "signing algorithm `HS256` is not allowed",
+            "issuer `joe` is not among accepted ones",
+            "audience (`aud`) is missing",
+            "subject (`sub`) is missing",
+            "required claim `jti` is missing",
+        ]
+    );
+    // Claims are still displayed.
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+#[wasm_bindgen_test]
+async fn claims_with_invalid_format_are_badged() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let claims = Claims::new(serde_json::json!({
+        "iss": "joe",
+        "email": "not an email",
+        "email_verified": "yes",
+        "locale": "en-US",
+        "scope": "email user_profile",
+    }));
+    let token = Hs256
+        .token(&Header::empty(), &claims, &Hs256Key::new(HS256_KEY))
+        .unwrap();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let email = extract_main_value(&claims["Email"]);
+    assert_eq!(email, "\"not an email\" expected an email address");
+    let email_verified = extract_main_value(&claims["Email verified?"]);
+    assert_eq!(email_verified, "\"yes\" expected a boolean");
+    for valid_claim in ["Issuer", "Locale", "OAuth 2.0 scopes"] {
+        assert_no_child(&claims[valid_claim], ".badge.bg-warning");
+    }
+}
+
+#[wasm_bindgen_test]
+async fn claim_formats_and_category_descriptions_are_displayed() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let token = UntrustedToken::new(HS256_TOKEN).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let format = select_single_element(&claims["Issuer"], ".claim-format");
+    let format = format.text_content().unwrap();
+    assert!(
+        format.starts_with("Format: Application-specific"),
+        "{format}"
+    );
+    assert_no_child(&claims["http://example.com/is_root"], ".claim-format");
+
+    let category = select_single_element(&rig.root_element, "#claim-cat-basic");
+    let description = select_single_element(&category, ".claim-category-description");
+    let description = description.text_content().unwrap();
+    assert!(description.contains("original JWT RFC"), "{description}");
+}
+
+async fn assert_claims_for_ecdsa_token(jwk: &str, token: &str) {
+    let rig = TestRig::new(AppProperties::default());
+    let key = serde_json::from_str(jwk).unwrap();
+    let key = KeyInstance::new(&key).unwrap();
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let token = UntrustedToken::new(token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let claims = get_decoded_claims(&rig.root_element);
+    assert_eq!(claims.len(), 3, "{claims:?}");
     let issuer = extract_main_value(&claims["Issuer"]);
     assert_eq!(issuer, "\"joe\"");
 }
 
+#[wasm_bindgen_test]
+async fn claims_are_displayed_for_correct_es256_token() {
+    assert_claims_for_ecdsa_token(P256_JWK, ES256_TOKEN).await;
+}
+
+#[wasm_bindgen_test]
+async fn claims_are_displayed_for_correct_es384_token() {
+    assert_claims_for_ecdsa_token(P384_JWK, ES384_TOKEN).await;
+}
+
+#[wasm_bindgen_test]
+async fn claims_are_displayed_for_correct_es512_token() {
+    assert_claims_for_ecdsa_token(P521_JWK, ES512_TOKEN).await;
+}
+
 #[wasm_bindgen_test]
 async fn error_is_displayed_for_incorrect_key_type() {
     let rig = TestRig::new(AppProperties::default());
@@ -82,3 +331,710 @@ async fn error_is_displayed_for_incorrect_key_type() {
     let claims = get_decoded_claims(&rig.root_element);
     assert_eq!(claims.len(), 3, "{claims:?}");
 }
+
+#[wasm_bindgen_test]
+async fn compatible_key_from_key_set_is_used_for_verification() {
+    let rig = TestRig::new(AppProperties::default());
+    let keys = [K256_JWK, P384_JWK, P256_JWK].map(|jwk| {
+        let jwk = serde_json::from_str(jwk).unwrap();
+        KeyInstance::new(&jwk).unwrap()
+    });
+    let entries = keys
+        .into_iter()
+        .enumerate()
+        .map(|(i, key)| KeySetEntry::new(i, None, key))
+        .collect();
+    rig.send_message(AppMessage::new_key_set(KeySet::new(entries)))
+        .await;
+    let token = UntrustedToken::new(ES256_TOKEN).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let verifying_key = select_single_element(&rig.root_element, "#verifying-key");
+    let verifying_key = verifying_key.text_content().unwrap();
+    assert!(verifying_key.contains("key #3"), "{verifying_key}");
+}
+
+fn symmetric_key_set(secrets: &[&[u8]]) -> Option<KeySet> {
+    let entries = secrets
+        .iter()
+        .enumerate()
+        .map(|(i, secret)| {
+            let key = KeyInstance::Symmetric(SecretBytes::owned(secret.to_vec()));
+            KeySetEntry::new(i, Some(format!("key-{i}")), key)
+        })
+        .collect();
+    KeySet::new(entries)
+}
+
+fn token_with_key_id(secret: &[u8], key_id: &str) -> UntrustedToken<'static> {
+    let header = Header::empty().with_key_id(key_id);
+    let claims = Claims::new(serde_json::json!({ "iss": "joe" }));
+    let token = Hs256
+        .token(&header, &claims, &Hs256Key::new(secret))
+        .unwrap();
+    UntrustedToken::new(&token).unwrap().into_owned()
+}
+
+#[wasm_bindgen_test]
+async fn key_is_selected_from_key_set_by_key_id() {
+    const OTHER_KEY: &[u8] = b"super-secret-key-that-is-not-really-secret";
+
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_key_set(symmetric_key_set(&[
+        OTHER_KEY, HS256_KEY,
+    ])))
+    .await;
+    let token = token_with_key_id(HS256_KEY, "key-1");
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let verifying_key = select_single_element(&rig.root_element, "#verifying-key");
+    let verifying_key = verifying_key.text_content().unwrap();
+    assert!(
+        verifying_key.contains("key #2 (kid: key-1)"),
+        "{verifying_key}"
+    );
+
+    // If `kid` points to another key, other keys should not be tried.
+    let token = token_with_key_id(HS256_KEY, "key-0");
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("signature has failed verification"),
+        "{alert_text}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn generated_public_key_is_loaded_for_verification() {
+    let rig = TestRig::new(AppProperties::default());
+    sleep(Duration::ZERO).await; // wait for rig to fully initialize
+
+    let key_pair = GeneratedKeyPair::generate(KeyPairType::Secp256k1);
+    rig.send_message(AppMessage::new_key_pair(key_pair.clone()))
+        .await;
+    sleep(Duration::ZERO).await; // wait for the key input to process the key
+
+    let signing_key = serde_json::from_str(&key_pair.private_jwk).unwrap();
+    let signing_key = SigningKeyInstance::new(&signing_key).unwrap();
+    let claims = Claims::new(serde_json::json!({ "iss": "joe" }));
+    let token = signing_key
+        .sign_token(
+            "ES256K",
+            &GenericHeader::default().with_key_id(&key_pair.key_id),
+            &claims,
+        )
+        .unwrap();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+async fn generate_random_token(rig: &TestRig, alg: &'static str) {
+    sleep(Duration::ZERO).await; // wait for rig to fully initialize
+    rig.send_message(AppMessage::SetRandomTokenAlgorithm(alg))
+        .await;
+    rig.send_message(AppMessage::RandomToken).await;
+    sleep(Duration::ZERO).await; // wait for the inputs to process the key and token
+}
+
+#[wasm_bindgen_test]
+async fn random_symmetric_token_is_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    generate_random_token(&rig, "HS384").await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    assert_no_child(&rig.root_element, "#random-private-key");
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"https://justwebtoken.io/\"");
+}
+
+#[wasm_bindgen_test]
+async fn random_asymmetric_token_is_verified() {
+    for alg in ["EdDSA", "ES256K", "ES256"] {
+        let rig = TestRig::new(AppProperties::default());
+        generate_random_token(&rig, alg).await;
+
+        assert_no_child(&rig.root_element, ".card-alert");
+        let claims = get_decoded_claims(&rig.root_element);
+        assert!(claims.contains_key("Issuer"), "{claims:?}");
+
+        let private_key = select_single_element(&rig.root_element, "#random-private-key-content");
+        let private_key = private_key.text_content().unwrap();
+        let private_key: JsonWebKey<'_> = serde_json::from_str(&private_key).unwrap();
+        assert!(private_key.is_signing_key(), "{private_key:?}");
+    }
+}
+
+fn encrypted_token(raw_token: &str) -> Option<InputToken> {
+    let token = EncryptedToken::new(raw_token).unwrap();
+    Some(InputToken::Encrypted(token))
+}
+
+#[wasm_bindgen_test]
+async fn encrypted_token_is_decrypted() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(A128GCM_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(A128GCM_TOKEN)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    assert_no_child(&rig.root_element, "#verifying-key");
+    let decryption_info = select_single_element(&rig.root_element, "#decryption-info");
+    let decryption_info = decryption_info.text_content().unwrap();
+    assert!(decryption_info.contains("not signed"), "{decryption_info}");
+
+    let claims = get_decoded_claims(&rig.root_element);
+    assert_eq!(claims.len(), 3, "{claims:?}");
+    let subject = extract_main_value(&claims["Subject"]);
+    assert_eq!(subject, "\"alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn nested_token_is_verified_after_decryption() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(NESTED_JWE_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(
+        NESTED_JWE_TOKEN,
+    )))
+    .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let decryption_info = select_single_element(&rig.root_element, "#decryption-info");
+    let decryption_info = decryption_info.text_content().unwrap();
+    assert!(
+        decryption_info.contains("payload is a signed token"),
+        "{decryption_info}"
+    );
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let name = extract_main_value(&claims["Full name"]);
+    assert_eq!(name, "\"Alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn error_is_displayed_for_incorrect_decryption_key() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(NESTED_JWE_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(A128GCM_TOKEN)))
+        .await;
+
+    assert_no_child(&rig.root_element, "#decoded-claims");
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Error decrypting token"),
+        "{alert_text}"
+    );
+    assert!(alert_text.contains("invalid key length"), "{alert_text}");
+
+    // Key of the correct length, but with different contents.
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(&NESTED_JWE_KEY[..16]));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("authentication tag mismatch"),
+        "{alert_text}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn warning_is_displayed_for_encrypted_token_without_key() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_input_token(encrypted_token(A128GCM_TOKEN)))
+        .await;
+
+    assert_no_child(&rig.root_element, "#decoded-claims");
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(alert_text.contains("Cannot decrypt token"), "{alert_text}");
+}
+
+fn private_key_set(jwk: &str) -> Option<KeySet> {
+    let jwk: JsonWebKey<'_> = serde_json::from_str(jwk).unwrap();
+    let key = KeyInstance::new(&jwk).unwrap();
+    let private_key = SigningKeyInstance::new(&jwk).unwrap();
+    KeySet::new(vec![
+        KeySetEntry::new(0, None, key).with_private_key(private_key)
+    ])
+}
+
+#[wasm_bindgen_test]
+async fn token_encrypted_with_rsa_key_is_decrypted() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_key_set(private_key_set(RSA_PRIVATE_JWK)))
+        .await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(RSA_OAEP_TOKEN)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let claims = get_decoded_claims(&rig.root_element);
+    assert_eq!(claims.len(), 3, "{claims:?}");
+    let subject = extract_main_value(&claims["Subject"]);
+    assert_eq!(subject, "\"alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn nested_token_encrypted_with_ecdh_is_decrypted_and_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_key_set(private_key_set(P256_PRIVATE_JWK)))
+        .await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(ECDH_ES_TOKEN)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let decryption_info = select_single_element(&rig.root_element, "#decryption-info");
+    let decryption_info = decryption_info.text_content().unwrap();
+    assert!(
+        decryption_info.contains("payload is a signed token"),
+        "{decryption_info}"
+    );
+    let claims = get_decoded_claims(&rig.root_element);
+    let name = extract_main_value(&claims["Full name"]);
+    assert_eq!(name, "\"Alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn error_is_displayed_for_public_decryption_key() {
+    let rig = TestRig::new(AppProperties::default());
+    let jwk: JsonWebKey<'_> = serde_json::from_str(P256_JWK).unwrap();
+    let key = KeyInstance::new(&jwk).unwrap();
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    rig.send_message(AppMessage::new_input_token(encrypted_token(ECDH_ES_TOKEN)))
+        .await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(alert_text.contains("private parts"), "{alert_text}");
+}
+
+async fn assert_unsecured_token_is_refused(rig: &TestRig) {
+    let token = UntrustedToken::new(UNSECURED_TOKEN).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(alert_text.contains("Unsecured token"), "{alert_text}");
+    assert!(alert_text.contains("must never accept"), "{alert_text}");
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+#[wasm_bindgen_test]
+async fn unsecured_token_is_refused_without_key() {
+    let rig = TestRig::new(AppProperties::default());
+    assert_unsecured_token_is_refused(&rig).await;
+}
+
+#[wasm_bindgen_test]
+async fn unsecured_token_is_refused_with_key() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    assert_unsecured_token_is_refused(&rig).await;
+}
+
+fn get_token_layers(root_element: &Element) -> Vec<String> {
+    let layers = select_single_element(root_element, "#token-layers");
+    let layers = layers.query_selector_all("li").unwrap();
+    (0..layers.length())
+        .map(|i| layers.get(i).unwrap().text_content().unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn nested_token_layers_are_verified_with_own_keys() {
+    let rig = TestRig::new(AppProperties::default());
+    let key_set = symmetric_key_set(&[NESTED_JWS_INNER_KEY, NESTED_JWS_OUTER_KEY]);
+    rig.send_message(AppMessage::new_key_set(key_set)).await;
+    let token = InputToken::new(NESTED_JWS_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let layers = get_token_layers(&rig.root_element);
+    assert_eq!(layers.len(), 2, "{layers:?}");
+    assert!(layers[0].contains("cty: JWT"), "{layers:?}");
+    assert!(layers[0].contains("verified with key #2"), "{layers:?}");
+    assert!(layers[1].contains("kid: key-0"), "{layers:?}");
+    assert!(layers[1].contains("verified with key #1"), "{layers:?}");
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let name = extract_main_value(&claims["Full name"]);
+    assert_eq!(name, "\"Alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn error_is_displayed_for_invalid_outer_layer() {
+    let rig = TestRig::new(AppProperties::default());
+    let key_set = symmetric_key_set(&[NESTED_JWS_INNER_KEY]);
+    rig.send_message(AppMessage::new_key_set(key_set)).await;
+    let token = InputToken::new(NESTED_JWS_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("signature has failed verification"),
+        "{alert_text}"
+    );
+
+    let layers = get_token_layers(&rig.root_element);
+    assert_eq!(layers.len(), 2, "{layers:?}");
+    assert!(layers[0].contains("invalid"), "{layers:?}");
+    assert!(layers[1].contains("verified"), "{layers:?}");
+    // Claims of the inner token are still displayed.
+    let claims = get_decoded_claims(&rig.root_element);
+    assert!(claims.contains_key("Full name"), "{claims:?}");
+}
+
+/// Creates a key set with the symmetric key for [`GENERAL_JSON_TOKEN`] and, optionally,
+/// a P-256 key with `kid: p256`.
+fn json_token_key_set(p256_jwk: Option<&str>) -> Option<KeySet> {
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(JSON_TOKEN_KEY));
+    let mut entries = vec![KeySetEntry::new(0, Some("key-0".to_owned()), key)];
+    if let Some(jwk) = p256_jwk {
+        let jwk: JsonWebKey<'_> = serde_json::from_str(jwk).unwrap();
+        let key = KeyInstance::new(&jwk).unwrap();
+        entries.push(KeySetEntry::new(1, Some("p256".to_owned()), key));
+    }
+    KeySet::new(entries)
+}
+
+fn get_signature_checks(root_element: &Element) -> Vec<String> {
+    let signatures = select_single_element(root_element, "#token-signatures");
+    let signatures = signatures.query_selector_all("li").unwrap();
+    (0..signatures.length())
+        .map(|i| signatures.get(i).unwrap().text_content().unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn all_signatures_of_json_token_are_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_key_set(json_token_key_set(Some(
+        P256_PRIVATE_JWK,
+    ))))
+    .await;
+    let token = InputToken::new(GENERAL_JSON_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let signatures = get_signature_checks(&rig.root_element);
+    assert_eq!(signatures.len(), 2, "{signatures:?}");
+    assert!(
+        signatures[0].contains("verified with key #1"),
+        "{signatures:?}"
+    );
+    assert!(
+        signatures[1].contains("verified with key #2"),
+        "{signatures:?}"
+    );
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let name = extract_main_value(&claims["Full name"]);
+    assert_eq!(name, "\"Alice\"");
+}
+
+#[wasm_bindgen_test]
+async fn json_token_signatures_are_verified_separately() {
+    let rig = TestRig::new(AppProperties::default());
+    // `P256_JWK` differs from the key used to sign the token.
+    rig.send_message(AppMessage::new_key_set(json_token_key_set(Some(P256_JWK))))
+        .await;
+    let token = InputToken::new(GENERAL_JSON_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(alert_text.contains("1 of 2"), "{alert_text}");
+
+    let signatures = get_signature_checks(&rig.root_element);
+    assert!(signatures[0].contains("verified"), "{signatures:?}");
+    assert!(signatures[1].contains("invalid"), "{signatures:?}");
+    // Claims are still displayed.
+    let claims = get_decoded_claims(&rig.root_element);
+    assert!(claims.contains_key("Full name"), "{claims:?}");
+}
+
+#[wasm_bindgen_test]
+async fn json_token_signatures_without_matching_key_are_not_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    rig.send_message(AppMessage::new_key_set(json_token_key_set(None)))
+        .await;
+    let token = InputToken::new(GENERAL_JSON_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let signatures = get_signature_checks(&rig.root_element);
+    assert_eq!(signatures.len(), 2, "{signatures:?}");
+    assert!(
+        signatures[0].contains("verified") && !signatures[0].contains("not verified"),
+        "{signatures:?}"
+    );
+    assert!(signatures[1].contains("not verified"), "{signatures:?}");
+    assert!(signatures[1].contains("no matching key"), "{signatures:?}");
+
+    let claims = get_decoded_claims(&rig.root_element);
+    assert!(claims.contains_key("Full name"), "{claims:?}");
+}
+
+async fn send_detached_token(rig: &TestRig, raw_token: &str, payload: Option<&str>) {
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let mut token = DetachedToken::new(raw_token).unwrap();
+    if let Some(payload) = payload {
+        token = token.with_detached_payload(payload);
+    }
+    let token = InputToken::Detached(token);
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+}
+
+#[wasm_bindgen_test]
+async fn token_with_detached_payload_is_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    send_detached_token(&rig, DETACHED_TOKEN, Some(DETACHED_TOKEN_PAYLOAD)).await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let claims = get_decoded_claims(&rig.root_element);
+    let subject = extract_main_value(&claims["Subject"]);
+    assert_eq!(subject, "\"webhook\"");
+}
+
+#[wasm_bindgen_test]
+async fn token_with_unencoded_payload_is_verified() {
+    let rig = TestRig::new(AppProperties::default());
+    send_detached_token(&rig, UNENCODED_TOKEN, None).await;
+
+    assert_no_child(&rig.root_element, ".card-alert");
+    let claims = get_decoded_claims(&rig.root_element);
+    assert!(claims.contains_key("Subject"), "{claims:?}");
+}
+
+#[wasm_bindgen_test]
+async fn warning_is_displayed_for_missing_detached_payload() {
+    let rig = TestRig::new(AppProperties::default());
+    send_detached_token(&rig, DETACHED_TOKEN, None).await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(alert_text.contains("payload is detached"), "{alert_text}");
+}
+
+#[wasm_bindgen_test]
+async fn error_is_displayed_for_modified_detached_payload() {
+    let rig = TestRig::new(AppProperties::default());
+    let payload = DETACHED_TOKEN_PAYLOAD.replace("push", "pull");
+    send_detached_token(&rig, DETACHED_TOKEN, Some(&payload)).await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("signature has failed verification"),
+        "{alert_text}"
+    );
+    // Claims are still displayed.
+    let claims = get_decoded_claims(&rig.root_element);
+    assert!(claims.contains_key("Subject"), "{claims:?}");
+}
+
+#[wasm_bindgen_test]
+async fn token_with_unsupported_critical_header_is_refused() {
+    let rig = TestRig::new(AppProperties::default());
+    let key = KeyInstance::Symmetric(SecretBytes::borrowed(HS256_KEY));
+    rig.send_message(AppMessage::new_key(Some(key))).await;
+    let token = InputToken::new(CRITICAL_HEADER_TOKEN).unwrap();
+    rig.send_message(AppMessage::new_input_token(Some(token)))
+        .await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Unsupported critical header"),
+        "{alert_text}"
+    );
+    assert!(alert_text.contains("not supported: exp."), "{alert_text}");
+
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+#[wasm_bindgen_test]
+async fn token_is_verified_with_embedded_key_on_request() {
+    let rig = TestRig::new(AppProperties::default());
+    sleep(Duration::ZERO).await; // wait for rig to fully initialize
+    let (token, jwk) = token_with_embedded_key();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+    // The embedded key is not used without an explicit request.
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Cannot verify integrity"),
+        "{alert_text}"
+    );
+
+    let jwk = serde_json::to_string(&jwk).unwrap();
+    rig.send_message(AppMessage::UseEmbeddedKey(jwk)).await;
+    sleep(Duration::ZERO).await; // wait for the key input to process the key
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Token is verified with embedded key"),
+        "{alert_text}"
+    );
+    let claims = get_decoded_claims(&rig.root_element);
+    let issuer = extract_main_value(&claims["Issuer"]);
+    assert_eq!(issuer, "\"joe\"");
+}
+
+fn certificate_key_set(thumbprints: CertificateThumbprints) -> Option<KeySet> {
+    let jwk: JsonWebKey<'_> = serde_json::from_str(P256_PRIVATE_JWK).unwrap();
+    let key = KeyInstance::new(&jwk).unwrap();
+    let entry = KeySetEntry::new(0, None, key).with_certificate(thumbprints);
+    KeySet::new(vec![entry])
+}
+
+#[wasm_bindgen_test]
+async fn certificate_thumbprint_is_checked_on_verification() {
+    let rig = TestRig::new(AppProperties::default());
+    let thumbprints = CertificateThumbprints {
+        sha1: X5C_LEAF_SHA1_THUMBPRINT.to_owned(),
+        sha256: X5C_LEAF_SHA256_THUMBPRINT.to_owned(),
+    };
+    rig.send_message(AppMessage::new_key_set(certificate_key_set(thumbprints)))
+        .await;
+    let token = token_with_certificate_chain();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    assert_no_child(&rig.root_element, ".card-alert.border-warning");
+    let claims = get_decoded_claims(&rig.root_element);
+    let subject = extract_main_value(&claims["Subject"]);
+    assert_eq!(subject, "\"alice\"");
+}
+
+/// Enters `value` into a textarea in the same way as the user would.
+async fn input_text(root_element: &Element, selector: &str, value: &str) {
+    let textarea = select_single_element(root_element, selector);
+    let textarea = textarea.dyn_into::<HtmlTextAreaElement>().unwrap();
+    textarea.set_value(value);
+    let event = InputEvent::new("input").unwrap();
+    textarea.dispatch_event(&event).unwrap();
+    sleep(Duration::ZERO).await;
+}
+
+#[wasm_bindgen_test]
+async fn certificate_chain_is_validated_at_evaluation_time() {
+    let rig = TestRig::new(AppProperties::default());
+    input_text(&rig.root_element, "#token", &token_with_certificate_chain()).await;
+    input_text(&rig.root_element, "#x5c-root", X5C_ROOT_CERTIFICATE).await;
+    select_single_element(&rig.root_element, "#x5c-validation.text-success");
+
+    // Before the validity period of all certificates in the chain.
+    let evaluated_at = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+    rig.send_message(AppMessage::SetEvaluationTime(Some(evaluated_at)))
+        .await;
+    let validation = select_single_element(&rig.root_element, "#x5c-validation.text-danger");
+    let validation_text = validation.text_content().unwrap();
+    assert!(
+        validation_text.contains("not valid yet"),
+        "{validation_text}"
+    );
+
+    // Inside the validity period.
+    let evaluated_at = Utc.with_ymd_and_hms(2021, 6, 1, 0, 0, 0).unwrap();
+    rig.send_message(AppMessage::SetEvaluationTime(Some(evaluated_at)))
+        .await;
+    let validation = select_single_element(&rig.root_element, "#x5c-validation.text-success");
+    let validation_text = validation.text_content().unwrap();
+    assert!(
+        validation_text.contains("chain is valid"),
+        "{validation_text}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn certificate_thumbprint_mismatch_is_reported() {
+    let rig = TestRig::new(AppProperties::default());
+    let thumbprints = CertificateThumbprints {
+        sha1: X5C_LEAF_SHA1_THUMBPRINT.to_owned(),
+        sha256: "AAAA".to_owned(),
+    };
+    rig.send_message(AppMessage::new_key_set(certificate_key_set(thumbprints)))
+        .await;
+    let token = token_with_certificate_chain();
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Certificate thumbprint mismatch"),
+        "{alert_text}"
+    );
+    assert!(alert_text.contains("x5t#S256"), "{alert_text}");
+    assert!(
+        alert_text.contains(X5C_LEAF_SHA256_THUMBPRINT),
+        "{alert_text}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn non_standard_certificate_thumbprint_is_reported_verbatim() {
+    let rig = TestRig::new(AppProperties::default());
+    let thumbprints = CertificateThumbprints {
+        sha1: X5C_LEAF_SHA1_THUMBPRINT.to_owned(),
+        sha256: X5C_LEAF_SHA256_THUMBPRINT.to_owned(),
+    };
+    rig.send_message(AppMessage::new_key_set(certificate_key_set(thumbprints)))
+        .await;
+
+    // Hex-encoded SHA-1 digest, which is 40 bytes long rather than 20.
+    let x5t = Base64UrlUnpadded::encode_string(b"5125709bad2c4c47468bbb08b62c60a788f6fefa");
+    let mut fields = serde_json::Map::new();
+    fields.insert("x5t".to_owned(), format!("{x5t}=").into());
+    let token = sign_p256_token(
+        GenericHeader::new(fields),
+        serde_json::json!({ "sub": "alice" }),
+    );
+    let token = UntrustedToken::new(&token).unwrap().into_owned();
+    rig.send_message(AppMessage::new_token(Some(token))).await;
+
+    assert_no_child(&rig.root_element, ".card-alert.border-danger");
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-warning");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("Certificate thumbprint mismatch"),
+        "{alert_text}"
+    );
+    assert!(alert_text.contains(&format!("x5t: {x5t},")), "{alert_text}");
+    assert!(
+        alert_text.contains(X5C_LEAF_SHA1_THUMBPRINT),
+        "{alert_text}"
+    );
+}
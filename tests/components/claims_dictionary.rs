@@ -0,0 +1,117 @@
+//! Tests for `<ClaimsDictionary />` component.
+
+use gloo_timers::future::sleep;
+use wasm_bindgen_test::*;
+
+use std::time::Duration;
+
+use super::{assert_no_child, select_elements, select_single_element, TestRigBase};
+use justwebtoken_io::components::claims_dictionary::{ClaimsDictionary, ClaimsDictionaryMessage};
+
+struct TestRig {
+    base: TestRigBase<ClaimsDictionary>,
+}
+
+impl TestRig {
+    async fn new() -> Self {
+        let base = TestRigBase::new(());
+        sleep(Duration::ZERO).await; // Wait until the component is rendered
+        Self { base }
+    }
+
+    fn ids(&self, selector: &str, prefix: &str) -> Vec<String> {
+        select_elements(&self.base.root_element, selector)
+            .map(|element| {
+                let id = element.id();
+                id.strip_prefix(prefix).unwrap_or(&id).to_owned()
+            })
+            .collect()
+    }
+
+    fn displayed_claims(&self) -> Vec<String> {
+        self.ids("div[id^=claim-]", "claim-")
+    }
+
+    fn displayed_categories(&self) -> Vec<String> {
+        self.ids("h3[id^=category-]", "category-")
+    }
+}
+
+#[wasm_bindgen_test]
+async fn all_claims_are_displayed_by_default() {
+    let rig = TestRig::new().await;
+
+    let categories = rig.displayed_categories();
+    assert_eq!(categories[..2], ["basic", "openid_connect"]);
+    let claims = rig.displayed_claims();
+    for name in ["exp", "iat", "sub", "email", "email_verified"] {
+        assert!(claims.iter().any(|claim| claim == name), "{claims:?}");
+    }
+    assert_no_child(&rig.base.root_element, "#claims-not-found");
+
+    let claim = select_single_element(&rig.base.root_element, "#claim-exp");
+    let label = select_single_element(&claim, "strong");
+    assert_eq!(label.text_content().unwrap(), "Expiration timestamp");
+    let anchor = select_single_element(&claim, ".claim-anchor");
+    assert_eq!(anchor.get_attribute("href").unwrap(), "#claim-exp");
+
+    let heading = select_single_element(&rig.base.root_element, "#category-basic");
+    let anchor = select_single_element(&heading, ".claim-anchor");
+    assert_eq!(anchor.get_attribute("href").unwrap(), "#category-basic");
+}
+
+#[wasm_bindgen_test]
+async fn claims_can_be_searched_by_name() {
+    let rig = TestRig::new().await;
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetQuery("EMail".to_owned()))
+        .await;
+
+    assert_eq!(rig.displayed_claims(), ["email", "email_verified"]);
+    assert_eq!(rig.displayed_categories(), ["openid_connect"]);
+
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetQuery(String::new()))
+        .await;
+    assert!(rig.displayed_claims().len() > 2);
+}
+
+#[wasm_bindgen_test]
+async fn claims_can_be_searched_by_description() {
+    let rig = TestRig::new().await;
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetQuery("as expired".to_owned()))
+        .await;
+    assert_eq!(rig.displayed_claims(), ["exp"]);
+
+    // HTML markup in descriptions is not searched.
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetQuery("href".to_owned()))
+        .await;
+    assert!(rig.displayed_claims().is_empty());
+    select_single_element(&rig.base.root_element, "#claims-not-found");
+}
+
+#[wasm_bindgen_test]
+async fn claims_can_be_filtered_by_category() {
+    let rig = TestRig::new().await;
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetCategory(Some("basic")))
+        .await;
+
+    assert_eq!(rig.displayed_categories(), ["basic"]);
+    let claims = rig.displayed_claims();
+    assert_eq!(claims.len(), 7, "{claims:?}");
+    assert!(claims.iter().any(|claim| claim == "exp"), "{claims:?}");
+
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetQuery("email".to_owned()))
+        .await;
+    assert!(rig.displayed_claims().is_empty());
+    select_single_element(&rig.base.root_element, "#claims-not-found");
+
+    rig.base
+        .send_message(ClaimsDictionaryMessage::SetCategory(None))
+        .await;
+    assert_eq!(rig.displayed_claims(), ["email", "email_verified"]);
+}
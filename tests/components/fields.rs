@@ -0,0 +1,108 @@
+//! Tests for claim format rules.
+
+use serde_json::json;
+use wasm_bindgen_test::*;
+
+use justwebtoken_io::fields::FormatRule;
+
+/// Rules together with valid and invalid values for each of them.
+fn format_rule_samples() -> Vec<(FormatRule, Vec<serde_json::Value>, Vec<serde_json::Value>)> {
+    vec![
+        (FormatRule::String, vec![json!("John")], vec![json!(42)]),
+        (
+            FormatRule::Uri,
+            vec![json!("urn:x"), json!("https://example.com/path")],
+            vec![json!("no scheme"), json!("1http://x"), json!("urn:")],
+        ),
+        (FormatRule::Bool, vec![json!(true)], vec![json!("true")]),
+        (
+            FormatRule::Phone,
+            vec![json!("+1 (555) 010-0000"), json!("+44.20.7946.0000")],
+            vec![
+                json!("555-0100"),
+                json!("+1234567890123456"), // 16 digits
+                json!("+1 555 x"),
+            ],
+        ),
+        (
+            FormatRule::Email,
+            vec![json!("john.doe@example.com")],
+            vec![json!("john.doe@localhost"), json!("@example.com")],
+        ),
+        (
+            FormatRule::Locale,
+            vec![json!("en_US"), json!("zh-Hant-TW")],
+            vec![json!("e"), json!("en US")],
+        ),
+        (
+            FormatRule::Zoneinfo,
+            vec![
+                json!("Europe/Paris"),
+                json!("America/Argentina/Buenos_Aires"),
+                json!("UTC"),
+            ],
+            vec![json!("/Paris"), json!("Europe/"), json!("Europe Paris")],
+        ),
+        (
+            FormatRule::Date,
+            vec![
+                json!("1990-12-31"),
+                json!("2024-02-29"),
+                json!("0000-02-29"), // year is omitted
+                json!("1990"),
+            ],
+            vec![
+                json!("2023-02-30"),
+                json!("2023-02-29"), // not a leap year
+                json!("0000-02-30"),
+                json!("1990-1-1"),
+                json!("90"),
+            ],
+        ),
+        (
+            FormatRule::SpaceList,
+            vec![json!("openid email"), json!("openid")],
+            vec![json!("openid  email"), json!(" openid"), json!("")],
+        ),
+        (
+            FormatRule::Timestamp,
+            vec![json!(1_300_819_380)],
+            vec![json!(1.5), json!("1300819380")],
+        ),
+        (
+            FormatRule::StringOrArray,
+            vec![json!("aud"), json!(["aud", "other"])],
+            vec![json!(["aud", 1])],
+        ),
+        (
+            FormatRule::StringArray,
+            vec![json!(["pwd", "otp"])],
+            vec![json!("pwd")],
+        ),
+        (
+            FormatRule::Object,
+            vec![json!({ "country": "US" })],
+            vec![json!("US")],
+        ),
+    ]
+}
+
+#[wasm_bindgen_test]
+fn format_rules_accept_valid_values() {
+    for (rule, valid_values, _) in format_rule_samples() {
+        for value in valid_values {
+            let res = rule.check(&value);
+            assert!(res.is_ok(), "{rule:?} rejected {value}");
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn format_rules_reject_invalid_values() {
+    for (rule, _, invalid_values) in format_rule_samples() {
+        for value in invalid_values {
+            let err = rule.check(&value).unwrap_err();
+            assert_eq!(err.rule, rule, "{value}");
+        }
+    }
+}
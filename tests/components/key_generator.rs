@@ -0,0 +1,99 @@
+//! Tests for `<KeyGenerator />` component.
+
+use assert_matches::assert_matches;
+use jwt_compact::jwk::JsonWebKey;
+use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen_test::*;
+use yew::Callback;
+
+use std::{cell::RefCell, rc::Rc};
+
+use super::{select_single_element, TestRigBase};
+use justwebtoken_io::{
+    components::key_generator::{
+        GeneratedKeyPair, KeyGenerator, KeyGeneratorMessage, KeyGeneratorProperties,
+    },
+    keys::{KeyInstance, KeyPairType, SigningKeyInstance},
+};
+
+struct TestRig {
+    base: TestRigBase<KeyGenerator>,
+    received_key_pair: Rc<RefCell<Option<GeneratedKeyPair>>>,
+}
+
+impl TestRig {
+    fn new() -> Self {
+        let received_key_pair = Rc::new(RefCell::new(None));
+        let received_key_pair_ = Rc::clone(&received_key_pair);
+        let props = KeyGeneratorProperties {
+            onchange: Callback::from(move |key_pair| {
+                *received_key_pair_.borrow_mut() = Some(key_pair);
+            }),
+        };
+
+        Self {
+            base: TestRigBase::new(props),
+            received_key_pair,
+        }
+    }
+
+    fn take_received_key_pair(&self) -> GeneratedKeyPair {
+        self.received_key_pair
+            .borrow_mut()
+            .take()
+            .expect_throw("No key pair received")
+    }
+
+    fn displayed_key(&self, selector: &str) -> String {
+        let snippet = select_single_element(&self.base.root_element, selector);
+        snippet.text_content().unwrap()
+    }
+}
+
+fn parse_key_pair(key_pair: &GeneratedKeyPair) -> (SigningKeyInstance, KeyInstance) {
+    let private_jwk: JsonWebKey<'_> = serde_json::from_str(&key_pair.private_jwk).unwrap();
+    assert!(private_jwk.is_signing_key());
+    let signing_key = SigningKeyInstance::new(&private_jwk).unwrap();
+
+    let public_jwk: JsonWebKey<'_> = serde_json::from_str(&key_pair.public_jwk).unwrap();
+    assert!(!public_jwk.is_signing_key());
+    let verifying_key = KeyInstance::new(&public_jwk).unwrap();
+    assert_eq!(verifying_key.thumbprint(), key_pair.key_id);
+    (signing_key, verifying_key)
+}
+
+#[wasm_bindgen_test]
+async fn generating_ed25519_key_pair() {
+    let rig = TestRig::new();
+    rig.base.send_message(KeyGeneratorMessage::Generate).await;
+
+    let key_pair = rig.take_received_key_pair();
+    let (signing_key, verifying_key) = parse_key_pair(&key_pair);
+    assert_matches!(signing_key, SigningKeyInstance::Ed25519(_));
+    assert_matches!(verifying_key, KeyInstance::Ed25519(_));
+
+    let public_jwk: serde_json::Value = serde_json::from_str(&key_pair.public_jwk).unwrap();
+    assert_eq!(public_jwk["kid"], key_pair.key_id.as_str());
+    assert_eq!(
+        rig.displayed_key("#generated-public-key"),
+        key_pair.public_jwk
+    );
+    assert_eq!(
+        rig.displayed_key("#generated-private-key"),
+        key_pair.private_jwk
+    );
+}
+
+#[wasm_bindgen_test]
+async fn generating_p256_key_pair() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(KeyGeneratorMessage::SetKeyType(KeyPairType::P256))
+        .await;
+    rig.base.send_message(KeyGeneratorMessage::Generate).await;
+
+    let key_pair = rig.take_received_key_pair();
+    let (signing_key, verifying_key) = parse_key_pair(&key_pair);
+    assert_matches!(signing_key, SigningKeyInstance::P256(_));
+    assert_matches!(verifying_key, KeyInstance::P256(_));
+}
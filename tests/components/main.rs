@@ -2,6 +2,7 @@
 
 use const_decoder::Decoder;
 use gloo_timers::future::sleep;
+use jwt_compact::{jwk::JsonWebKey, Claims};
 use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use wasm_bindgen_test::wasm_bindgen_test_configure;
 use web_sys::Element;
@@ -9,9 +10,15 @@ use yew::{AppHandle, Component, Renderer};
 
 use std::{collections::HashMap, time::Duration};
 
+use justwebtoken_io::keys::{GenericHeader, SigningKeyInstance};
+
 mod app;
+mod claims_dictionary;
+mod fields;
+mod key_generator;
 mod key_input;
 mod token_input;
+mod token_signer;
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -30,6 +37,315 @@ const K256_JWK: &str = r#"
     }
 "#;
 
+/// Symmetric key from [RFC 7515, appendix A.1](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.1).
+const HS256_JWK: &str = r#"
+    {
+        "kty": "oct",
+        "k": "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"
+    }
+"#;
+
+/// Taken from [RFC 7519, section 6.1](https://www.rfc-editor.org/rfc/rfc7519.html#section-6.1).
+const UNSECURED_TOKEN: &str = "eyJhbGciOiJub25lIn0.\
+    eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.";
+
+/// Taken from [RFC 7515, appendix A.3](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.3).
+const ES256_TOKEN: &str = "eyJhbGciOiJFUzI1NiJ9.\
+    eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly\
+    9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5d\
+    jxLa8ISlSApmWQxfKTUJqPP3-Kg6NU1Q";
+const P256_JWK: &str = r#"
+    {
+        "crv": "P-256",
+        "kty": "EC",
+        "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+        "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0"
+    }
+"#;
+/// Randomly generated private key.
+const P256_PRIVATE_JWK: &str = r#"
+    {
+        "crv": "P-256",
+        "kty": "EC",
+        "x": "FTPBy7FaPKYHsoPBB5_eurUCrDvyNhA0pnSn0w5gxiE",
+        "y": "DnlFu8Xko3VBQukfUxuD53IAZktWJ_nZNowEnv6rsWc",
+        "d": "eWX179TDDL4DtsV53LBGSJBvxf54pX_7Sn95yz8I-v4"
+    }
+"#;
+/// Generated with a random key.
+const ES384_TOKEN: &str = "eyJhbGciOiJFUzM4NCIsInR5cCI6IkpXVCJ9.\
+    eyJleHAiOjEzMDA4MTkzODAsImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlLCJpc3MiOiJqb2UifQ.\
+    0wDxD5TDSTCYlJ2fxpsIsEKaakBRbuxY7gQqDW-VqkyvwctY3tSyxz2PRWMwxFKI2rAnkGydniaAzOVRvR3Cqrbly\
+    ZV-AK1cyA_f1ZpDqgLdjSheBRqAOPhaZUU7cq6X";
+const P384_JWK: &str = r#"
+    {
+        "crv": "P-384",
+        "kty": "EC",
+        "x": "yZ-kJRHULHT5Xpw87HN01dEauUAO7ocsOIldwgHoIm1rnFbpvE-gRyCdEgOpN_93",
+        "y": "7BLPoPbeGc9NdI6GWJ04yKPVUNxHmFnrZKWw2qlM2eQKx5fYVB3d4qSu_Tnu_QjC"
+    }
+"#;
+/// Generated with a random key.
+const ES512_TOKEN: &str = "eyJhbGciOiJFUzUxMiIsInR5cCI6IkpXVCJ9.\
+    eyJleHAiOjEzMDA4MTkzODAsImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlLCJpc3MiOiJqb2UifQ.\
+    AYeSNnDMYkvpMhOKeNBgOdhV8sqlZ35dpJSEkj-lMxd5GOEfo5hE3QTXJNx-dGdku8_nt4gmtGS3bfisT4GfQvYnAbz1\
+    zHv-uTHEMd0E9QE05_PP8nBa7prAzEz5A46lXwV1MEUMZc0fgVCT1qeQgluTJ-4r9ydNS_JBwOdyI-NG2nnC";
+const P521_JWK: &str = r#"
+    {
+        "crv": "P-521",
+        "kty": "EC",
+        "x": "AG7e4HOLUk3P3yMTV-hFp4LSoCTW49KFyCt7uJ-okEWfEDjmBgqJ7I232UeO-UY0VV0z0TKQOtPE1DS6O9xv0FJl",
+        "y": "AX3LkXBhSXtjNbi-5LIAqaCWgfKF6RfAgihYDy4H1RmyNhXTf5KuKFxj99y2_DlVS9cyIk0yY8cXLMHATb0_TCXJ"
+    }
+"#;
+
+/// Generated with a random key; encrypted directly (`alg: dir`) with `enc: A128GCM`.
+/// The payload is a JSON claims object.
+const A128GCM_TOKEN: &str = "eyJhbGciOiJkaXIiLCJlbmMiOiJBMTI4R0NNIn0.\
+    .7hJyaF_GM1nOyfj0.y2nOrM0SaD5gheLbOZe9l0tyvQRVbskiysAFwkJ5Cv-NDW01PU6pdpnJjBUCvUBuY8W2jw.\
+    -I7gSO74k2MjHA-_F6OLsA";
+const A128GCM_KEY: &[u8] = &Decoder::Base64Url.decode::<16>(b"6908jcWiOjsdNXo3u9JvtA");
+/// Generated with a random key; encrypted directly (`alg: dir`) with `enc: A128CBC-HS256`.
+/// The payload is a token signed with HS256 using the same key.
+const NESTED_JWE_TOKEN: &str = "eyJhbGciOiJkaXIiLCJlbmMiOiJBMTI4Q0JDLUhTMjU2IiwiY3R5IjoiSldUIn0.\
+    .fMzHm5KvsrCGitjL58b-TQ.tmgZf6Kr9-fidlRPcH4lKiryADcRIPGNMAooXAdvk_0UvFWynd2qW8MJizu8ZW9zfA\
+    VjQpzEQpYO1g1Ldm7DqkCxvMWY6d1DpkSRnc5D41DfJoERk79Ov23PJHscd7VB3F_g95DwA5FfM8FnkuzP2sH0BmcI\
+    KO2wcTwJhlIevoG1z1oEPzhRoBuTt4KrJjQFDenCek8izydeo_QOMpwPqg.\
+    9EJ8mFUkCVG5H1BgfDkzhA";
+const NESTED_JWE_KEY: &[u8] =
+    &Decoder::Base64Url.decode::<32>(b"yujMxKGOawJtyY_UxavXtX0bayaPnUFE0r1DDYPfZvM");
+/// Generated with random keys. The outer token (`kid: key-1`, `cty: JWT`) wraps a token
+/// signed with another key (`kid: key-0`); both tokens use HS256.
+const NESTED_JWS_TOKEN: &str = "eyJhbGciOiJIUzI1NiIsImtpZCI6ImtleS0xIiwiY3R5IjoiSldUIn0\
+    .ZXlKaGJHY2lPaUpJVXpJMU5pSXNJbXRwWkNJNkltdGxlUzB3SW4wLmV5SnpkV0lpT2lKaGJHbGpaU0lzSW01aGJXVWl\
+    PaUpCYkdsalpTSXNJbWxoZENJNk1UY3dNREF3TURBd01IMC41ckQtSkdMU1k3V1EzSUZuVUtOWkx2NUhxdHZfSm5fR3Z\
+    ndFdXWERnUklV.aFOr0E_CNtg0y6R87CI81WNy_piOXfIQkT6cRSxtJgY";
+const NESTED_JWS_INNER_KEY: &[u8] =
+    &Decoder::Base64Url.decode::<32>(b"MaEzUxOLPG1gjXMJ0VtaYRhv_0Hs1Ro_qpPfUfgXAa4");
+const NESTED_JWS_OUTER_KEY: &[u8] =
+    &Decoder::Base64Url.decode::<32>(b"oKwAXgt8rWGLPN6OSTxa5hNDrWiml0fPPB5Rn2lyn8c");
+/// Generated with a random HS256 key (`kid: key-0`) and [`P256_PRIVATE_JWK`] (`kid: p256`).
+const GENERAL_JSON_TOKEN: &str = r#"
+    {
+        "payload": "eyJzdWIiOiJhbGljZSIsIm5hbWUiOiJBbGljZSIsImlhdCI6MTcwMDAwMDAwMH0",
+        "signatures": [
+            {
+                "protected": "eyJhbGciOiJIUzI1NiJ9",
+                "header": { "kid": "key-0" },
+                "signature": "xEj76Zieklx3yNeqRgNDsupNivKAemmv4Y3plRIw6jM"
+            },
+            {
+                "protected": "eyJhbGciOiJFUzI1NiJ9",
+                "header": { "kid": "p256" },
+                "signature": "vVgl-Sgvvq1dsHs77iybinsd4pCfTykDp2iGXVfP5n6gkYcP9u9RAKb_jTNvP7Lqt-hXwM7JDgn-VAmwQD0lQA"
+            }
+        ]
+    }
+"#;
+/// Same as the first signature in [`GENERAL_JSON_TOKEN`], but in the flattened syntax.
+const FLATTENED_JSON_TOKEN: &str = r#"
+    {
+        "payload": "eyJzdWIiOiJhbGljZSIsIm5hbWUiOiJBbGljZSIsImlhdCI6MTcwMDAwMDAwMH0",
+        "protected": "eyJhbGciOiJIUzI1NiJ9",
+        "header": { "kid": "key-0" },
+        "signature": "xEj76Zieklx3yNeqRgNDsupNivKAemmv4Y3plRIw6jM"
+    }
+"#;
+const JSON_TOKEN_KEY: &[u8] =
+    &Decoder::Base64Url.decode::<32>(b"zxMgwPtsiPbEdBAfA15myeRZnV1BljN5PhEctxSy-8Y");
+/// Token with a detached unencoded payload (`b64: false`) signed with [`HS256_KEY`].
+const DETACHED_TOKEN: &str = "eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19..\
+    HBBbaxc8K0_fugZq-m8gRviuQkt3tGaLgHSDcdwGTmY";
+const DETACHED_TOKEN_PAYLOAD: &str = r#"{"sub":"webhook","event":"push","iat":1700000000}"#;
+/// Same as [`DETACHED_TOKEN`], but with the payload attached.
+const UNENCODED_TOKEN: &str = r#"eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2UsImNyaXQiOlsiYjY0Il19.{"sub":"webhook","event":"push","iat":1700000000}.HBBbaxc8K0_fugZq-m8gRviuQkt3tGaLgHSDcdwGTmY"#;
+/// Token signed with [`HS256_KEY`] with a critical header extension (`crit: ["exp"]`)
+/// and a vendor-specific header field.
+const CRITICAL_HEADER_TOKEN: &str =
+    "eyJhbGciOiJIUzI1NiIsImNyaXQiOlsiZXhwIl0sImV4cCI6MTM2MzI4NDAwMCwieC\
+    12ZW5kb3IiOnsiaWQiOjF9fQ.eyJpc3MiOiJqb2UiLCJzdWIiOiJhbGljZSJ9.\
+    QrEsLqhsxt8An63rX9zQPD1oi9DPNnwXhVGZtUaufLg";
+
+/// Signs a token with [`P256_PRIVATE_JWK`] using the specified header fields.
+fn sign_p256_token(header: GenericHeader, claims: serde_json::Value) -> String {
+    let jwk: JsonWebKey<'_> = serde_json::from_str(P256_PRIVATE_JWK).unwrap();
+    let signing_key = SigningKeyInstance::new(&jwk).unwrap();
+    let claims = Claims::new(claims);
+    signing_key.sign_token("ES256", &header, &claims).unwrap()
+}
+
+fn p256_public_jwk() -> serde_json::Value {
+    let jwk: JsonWebKey<'_> = serde_json::from_str(P256_PRIVATE_JWK).unwrap();
+    let signing_key = SigningKeyInstance::new(&jwk).unwrap();
+    let verifying_key = signing_key.to_verifying_key();
+    serde_json::to_value(verifying_key.to_jwk()).unwrap()
+}
+
+/// Creates a DPoP-like token signed with [`P256_PRIVATE_JWK`], which embeds the corresponding
+/// public key into the `jwk` header. Returns the token together with the embedded key.
+fn token_with_embedded_key() -> (String, serde_json::Value) {
+    let public_jwk = p256_public_jwk();
+    let mut fields = serde_json::Map::new();
+    fields.insert("jwk".to_owned(), public_jwk.clone());
+    let header = GenericHeader::new(fields).with_token_type("dpop+jwt");
+    let claims = serde_json::json!({
+        "htm": "POST",
+        "htu": "https://server.example.com/token",
+        "iss": "joe",
+    });
+    (sign_p256_token(header, claims), public_jwk)
+}
+
+/// Certificate chain for [`P256_PRIVATE_JWK`]: the leaf certificate (`CN=idp.example.com`,
+/// signed with ES384) and the issuing CA certificate (signed with RS256
+/// by [`X5C_ROOT_CERTIFICATE`]).
+const X5C_CHAIN: [&str; 2] = [
+    "MIIBhjCCAQygAwIBAgIBAzAKBggqhkjOPQQDAzA0MRUwEwYDVQQKDAxFeGFtcGxlIENvcnAxGzAZBgNVBAMMEkV4YW1wbGUg\
+    SXNzdWluZyBDQTAgFw0yMDAxMDEwMDAwMDBaGA8yMDk5MTIzMTAwMDAwMFowMTEVMBMGA1UECgwMRXhhbXBsZSBDb3JwMRgw\
+    FgYDVQQDDA9pZHAuZXhhbXBsZS5jb20wWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQVM8HLsVo8pgeyg8EHn966tQKsO/I2\
+    EDSmdKfTDmDGIQ55RbvF5KN1QULpH1Mbg+dyAGZLVif52TaMBJ7+q7FnoxAwDjAMBgNVHRMBAf8EAjAAMAoGCCqGSM49BAMD\
+    A2gAMGUCMQDucM1VZaaNwRn2/MSruWda5bH21mZp8Sux8vA3uGONNQySpihkFNpPfvAo2hqlH9YCMD8veRpYzM0MxkSIUQ7B\
+    ihINhWum6DUDalvjdlfV6QWrSo6Hb8iB7o0710BPfXlrvQ==",
+    "MIICRzCCAS+gAwIBAgIBAjANBgkqhkiG9w0BAQsFADAxMRUwEwYDVQQKDAxFeGFtcGxlIENvcnAxGDAWBgNVBAMMD0V4YW1w\
+    bGUgUm9vdCBDQTAgFw0yMDAxMDEwMDAwMDBaGA8yMDk5MTIzMTAwMDAwMFowNDEVMBMGA1UECgwMRXhhbXBsZSBDb3JwMRsw\
+    GQYDVQQDDBJFeGFtcGxlIElzc3VpbmcgQ0EwdjAQBgcqhkjOPQIBBgUrgQQAIgNiAATTjy6MGIKyHYJa9do4YOsQHC37NU9O\
+    lm1XAGMIzZivyEQoa/MPxTtK/gNns4yTn0mNCfdytINSsxBG+0iC4eX1hYFKJ6mPnvbNmgNvcBeHzv80tsePmL1He9AqhFQn\
+    MvmjEzARMA8GA1UdEwEB/wQFMAMBAf8wDQYJKoZIhvcNAQELBQADggEBAKcdH+a/K12T+zA5hvzhXsekfndP6iXALpADLDSY\
+    MUHgLHAa3LTTUxy3psRqt+Ln0Vt3FIkNp7RaRqzJQqyZNfKOemXlSEfDzasOjzR3hb8wfeY68O5j22HP266cMBu87X4GM55/\
+    4LydxcXlESqhJOgExQQGuCSlbuvpmKe6mXdin/v7unBCfUT5z+z5PhtOHTFPxd9Bt4Q4fzj+B4aXw0uwlyTcHqbTOLvPS7lB\
+    HdBkfdzkmNA+tIQNvKE9K5LND96JEAxLOZj7vnmz+VSic4o4NiViJgBuuSh0IOTEyMv0NLc8QElufOzGnXZHS6zIrGaA57Ef\
+    dZpdvme9Jy8GY7A=",
+];
+/// SHA-1 thumbprint of the leaf certificate from [`X5C_CHAIN`].
+const X5C_LEAF_SHA1_THUMBPRINT: &str = "SURwutkkxHaUuwi_YsCniPb-to8";
+/// SHA-256 thumbprint of the leaf certificate from [`X5C_CHAIN`].
+const X5C_LEAF_SHA256_THUMBPRINT: &str = "Ps5b2rJEV2nc-U5FhHs5WNo6Z8FMF826nGFg1KjOVcE";
+/// Root certificate for [`X5C_CHAIN`].
+const X5C_ROOT_CERTIFICATE: &str = r#"
+-----BEGIN CERTIFICATE-----
+MIIC8jCCAdqgAwIBAgIBATANBgkqhkiG9w0BAQsFADAxMRUwEwYDVQQKDAxFeGFt
+cGxlIENvcnAxGDAWBgNVBAMMD0V4YW1wbGUgUm9vdCBDQTAgFw0yMDAxMDEwMDAw
+MDBaGA8yMDk5MTIzMTAwMDAwMFowMTEVMBMGA1UECgwMRXhhbXBsZSBDb3JwMRgw
+FgYDVQQDDA9FeGFtcGxlIFJvb3QgQ0EwggEiMA0GCSqGSIb3DQEBAQUAA4IBDwAw
+ggEKAoIBAQCnx5MmpJ+wsQonNb25KfcmQ9OOJz+Nblm5lbcLgtp/RRQP9P2CplkC
+P45Uh57Rx4l5e/th7qxxg6KKbALvidqDVNS/JrIEKCsyP66UloEe6AYai4r41Guy
+iT/svYDjLyMdAOkOzwQIQS8Mqm9BP5k8+IGVd0Z/V2kgrDFsd8aphCRkyX1+loV0
+twpTIuf4sa6zYdYsVeGaNZ4gWLeq3w+FKBvbqJ1WL36dHY91YkdxvHagpQFOapHb
+kbdiN8bLawck1yoey8e1EfWNCSaghk+Oi+iqzftssU3q6nIykobccSPmmI6TbUr0
+U5KaOvsNl2uIMmcngocirGp8kuhpZK3fAgMBAAGjEzARMA8GA1UdEwEB/wQFMAMB
+Af8wDQYJKoZIhvcNAQELBQADggEBAGyxKgOmK7GO4pCupH1hDNYvmpgnM0GbmTS6
+D8jej9Z8+OFNLgv77z+WbdMVYU2ZxEWH+FbjoUeckJ1v3ysls9JID9Fxvn+t2jdW
+FxVg8dm5vcyrXQw9NhzCwqj3AWxh/RvKx+NyvZ2VxIubv8uJc9RqIYwZVg6TdJW7
+hpMrrlJItop4VR3SUxdwmBSyKLFCe8slRCrJgQHc28wMeTfj1KFD4csLxXMcAp1R
+pAw7VPH/OJ4F8Qw3mceEI7q3PIVbrklVI19qWX7SqENJNY9LciROY7s1wg+8xhT3
+I+4EpBOWcfKQpUdODdX4tPTiuWKB+43FX6CbaOGmXYvpAKJ97Rs=
+-----END CERTIFICATE-----
+"#;
+/// Root certificate with the same subject as [`X5C_ROOT_CERTIFICATE`], but a different key.
+const X5C_OTHER_ROOT_CERTIFICATE: &str = r#"
+-----BEGIN CERTIFICATE-----
+MIIC8jCCAdqgAwIBAgIBATANBgkqhkiG9w0BAQsFADAxMRUwEwYDVQQKDAxFeGFt
+cGxlIENvcnAxGDAWBgNVBAMMD0V4YW1wbGUgUm9vdCBDQTAgFw0yMDAxMDEwMDAw
+MDBaGA8yMDk5MTIzMTAwMDAwMFowMTEVMBMGA1UECgwMRXhhbXBsZSBDb3JwMRgw
+FgYDVQQDDA9FeGFtcGxlIFJvb3QgQ0EwggEiMA0GCSqGSIb3DQEBAQUAA4IBDwAw
+ggEKAoIBAQCtzHJgsI3JsMnGseXNsBTjTL1W5t0Lu6gSewHJvMlDZddrvzXRcZ1L
+/XvFbE4MDZG1I1FCFYs5N1ORoE+sPLDRF2dPHZKS2G6YYAez6D9WHH27I1H1qBsz
+/JoqH7GNLl3meqimMHRQWaSw9K4MIB4RMmcu4dabYp1K0KFt3aq9BXaZJOW/wqw9
+EdWMcWQbfSKDNWcsIEXJH5nWT5lWmDul3roBuPwWM5rh849NqnbywF8q49Ki4V8i
+mwlNTPQ7jkY0PUZJVTkkedY8ro8W47mjcPSkpENkBQ8an/zHh2qWeOKHNVFsHATC
+NTa0bchRS+JBpz2wdKafje9LWRly8I/fAgMBAAGjEzARMA8GA1UdEwEB/wQFMAMB
+Af8wDQYJKoZIhvcNAQELBQADggEBAEZYHli5IU/2zORi/VbKxGhuHOSgTRKkPYkr
+Jn42LpLCPphL/Z4A+cAiVWS70royMP+ubu1fLd6jj6IihGEyhZiF7rDOKQIwk1US
+1s8+2qLilsouDsOC9vmCOa0FuP4c5/ATOaNfPEFlrfJbCzKNdTvYhvNOQNKSj7ar
+XeGFO39jUZUpiuPWpb7Tb1aLU50/MmDjoEzSuqK47fYrbKZqo3v9015YIBGpmAfk
+mfI9SKZITX7wvlesJP/1TWlA3c48p6nMU+17ma0puGeKl31j9q8YGrMC0LPI+kj3
+BubuBRNkYj5nQS3TXi2jR7omVvRvzl0LwciKzJ7kfEiFpa7CQOk=
+-----END CERTIFICATE-----
+"#;
+
+/// [`X5C_CHAIN`] with the outer signature algorithm of the leaf certificate changed
+/// to `ecdsa-with-SHA256`, so that it differs from the algorithm in the signed certificate data.
+const X5C_MISMATCHED_ALGORITHM_CHAIN: [&str; 2] = [
+    "MIIBhjCCAQygAwIBAgIBAzAKBggqhkjOPQQDAzA0MRUwEwYDVQQKDAxFeGFtcGxlIENvcnAxGzAZBgNVBAMMEkV4YW1wbGUg\
+    SXNzdWluZyBDQTAgFw0yMDAxMDEwMDAwMDBaGA8yMDk5MTIzMTAwMDAwMFowMTEVMBMGA1UECgwMRXhhbXBsZSBDb3JwMRgw\
+    FgYDVQQDDA9pZHAuZXhhbXBsZS5jb20wWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQVM8HLsVo8pgeyg8EHn966tQKsO/I2\
+    EDSmdKfTDmDGIQ55RbvF5KN1QULpH1Mbg+dyAGZLVif52TaMBJ7+q7FnoxAwDjAMBgNVHRMBAf8EAjAAMAoGCCqGSM49BAMC\
+    A2gAMGUCMQDucM1VZaaNwRn2/MSruWda5bH21mZp8Sux8vA3uGONNQySpihkFNpPfvAo2hqlH9YCMD8veRpYzM0MxkSIUQ7B\
+    ihINhWum6DUDalvjdlfV6QWrSo6Hb8iB7o0710BPfXlrvQ==",
+    X5C_CHAIN[1],
+];
+
+/// Certificate chain with a leaf certificate signed with `ecdsa-with-SHA384` by a P-256 CA.
+/// This hash / curve combination has no JWS algorithm counterpart.
+const X5C_P256_SHA384_CHAIN: [&str; 2] = [
+    "MIIBpDCCAUugAwIBAgIBBTAKBggqhkjOPQQDAzAyMRUwEwYDVQQKDAxFeGFtcGxlIENvcnAxGTAXBgNVBAMMEEV4YW1wbGUg\
+    UC0yNTYgQ0EwIBcNMjAwMTAxMDAwMDAwWhgPMjA5OTEyMzEwMDAwMDBaMDIxFTATBgNVBAoMDEV4YW1wbGUgQ29ycDEZMBcG\
+    A1UEAwwQcDI1Ni5leGFtcGxlLmNvbTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABIuBJ7DLVT3RZMWV5VENN5IeEaejlZDN\
+    khQEaZy72a9epzT8AtUX0Nnr/TBLup7BDWNFYPu+OhodKqSLkGo9+P+jUDBOMAwGA1UdEwEB/wQCMAAwHQYDVR0OBBYEFFWc\
+    /gUT4RykaTWv82Ufdn+ab76eMB8GA1UdIwQYMBaAFEYKpebV9U4c84bASRLirkMRsvFEMAoGCCqGSM49BAMDA0cAMEQCIEuW\
+    Drktqr3Uve+M5Psmwl70XE+iIowVaIjpmOGhhtnBAiBm1PVIFdiQ3E4XuZaAtLJbmfxK5JCom42REq52zrSvHg==",
+    "MIIBujCCAWGgAwIBAgIUWrBorl96SIGRxGUhEO591J0FGYYwCgYIKoZIzj0EAwIwMjEVMBMGA1UECgwMRXhhbXBsZSBDb3Jw\
+    MRkwFwYDVQQDDBBFeGFtcGxlIFAtMjU2IENBMCAXDTIwMDEwMTAwMDAwMFoYDzIwOTkxMjMxMDAwMDAwWjAyMRUwEwYDVQQK\
+    DAxFeGFtcGxlIENvcnAxGTAXBgNVBAMMEEV4YW1wbGUgUC0yNTYgQ0EwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAASeoFVo\
+    O8plvztjSqh8Iq3Bk3/6PAZ5YpYqG3HMYvZcWmbJ63EVNa5bjQAO9fe0mxIehK34AjhJetyhP5AEUDuMo1MwUTAdBgNVHQ4E\
+    FgQURgql5tX1ThzzhsBJEuKuQxGy8UQwHwYDVR0jBBgwFoAURgql5tX1ThzzhsBJEuKuQxGy8UQwDwYDVR0TAQH/BAUwAwEB\
+    /zAKBggqhkjOPQQDAgNHADBEAiArNVral3D5KH8znwkPJDxH7/ssU9XelEc7/eQtn/DSLgIgGzx0phROj76XuTCsRIVvpKTc\
+    XToaLleyVs4ffoAGCsM=",
+];
+
+/// Encodes the leaf certificate from [`X5C_CHAIN`] in the PEM format.
+fn x5c_leaf_certificate_pem() -> String {
+    let encoded = X5C_CHAIN[0].as_bytes();
+    let lines: Vec<_> = encoded
+        .chunks(64)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect();
+    let lines = lines.join("\n");
+    format!("-----BEGIN CERTIFICATE-----\n{lines}\n-----END CERTIFICATE-----")
+}
+
+/// Creates a token signed with [`P256_PRIVATE_JWK`] with the [`X5C_CHAIN`] certificate chain.
+fn token_with_certificate_chain() -> String {
+    let mut fields = serde_json::Map::new();
+    fields.insert("x5c".to_owned(), serde_json::json!(X5C_CHAIN));
+    fields.insert(
+        "x5t#S256".to_owned(),
+        X5C_LEAF_SHA256_THUMBPRINT.to_owned().into(),
+    );
+    let header = GenericHeader::new(fields);
+    let claims = serde_json::json!({ "iss": "https://idp.example.com/", "sub": "alice" });
+    sign_p256_token(header, claims)
+}
+
+/// Randomly generated RSA private key.
+const RSA_PRIVATE_JWK: &str = r#"
+    {
+        "kty": "RSA",
+        "n": "yWKm6oxKMG4FnGSMm5WDLNiWSo_FzKjAiRoXG3hxvfXHS_ZXPhjAvqf7LT1Yew-n_CTW2mVBI-qQhyCPoMMGc2dl3FP0JBCmNDIYNIMHeY0_VbANGF8anSF8G4SkB56EMLxEZNjIknBGJUGjT1BWs5B92O_TRLkc9bUs6URSjsd0gRpn8APap758kf9NYwubtRLKkEsydO1CStg8aAoVQ9FhpS4Uax9ZF4PlJAN5qLzrdnqUMq4V4Y8GfI1TGfAZXQGxVHnEooH9whOJ1qHKslHSZQjRqRVYS2gJLCaN5ST6wnJQhzYZIEjLbB1tktncyO9bjQFCc7tU9WGryCsN_Q",
+        "e": "AQAB",
+        "d": "A3IH44rnaQk02N5ihnXqIt4IwjlP-8amVqIm444tuf-Gu565EuOnBowGsNk0FpTnvZlewKlLUkM82kb9FhXIGjjaZgjcrDJJuapiviFAwzxeqUdkDfbwlGAiiLD-AnBcmB3ceLI5ykGX_aKwGdcIyLUv5gKCZZJaM7z4CfykgfNRXOH7PKl-t0J9VZ288JBEhr8woHGJO7oT2bAzpWXWM-BgjkDlEKhqq-lEVvVj1DjSB7LP9LKwePbnVNllNgGdhoxpsE2jZ-liU2rYzAf0A9D9JlLo_GnXlrY6CDiDoSnOdxgglMMqtjM3lqrI5wEWYaaT1qd8ytHjMRAKrs_WwQ",
+        "p": "7RlQsAm_-wFSDivE0SZ_riYrTYBZtsXPdCbSgvC2NNmdDKys0gMr8n2AhwwT98A7k3Epj0McVppr-xcwgwjbsGY4x-WA7RpxPgm3WA_2f_tyb-5D9eIt5Y_au7D_oTU2zUFa0ySKB_EJVp5onhXRyCAdPvVgjfKxV7Qq4Tj-Gr0",
+        "q": "2XB_4z8y1bedU5rlXhZBlyMW5KFmoFNu9EQWxZnr6Zzt-AQacbZwVDRP6RY6VGP9jC_RwS41hoGOoNQZ9ZcVi1hnewzV3Jmi_RGJERj6Kiy9fMNbBSR-_iooC65IzoJIGutLgYU0mzt6o3awg4HUG5GZUgXF7tNrl40TddSFlEE",
+        "dp": "GwKpSISz1DqASJD_6PvDy-kZQcj_b2z1hzvs-3HEsByiYZFCVuJ5nTRi8f-UPsMthkx7-ZAhqFQLPR2VLzhRHrVmfo9MsrYydGjS9DtNT6MrlEjGgTp1FBPhTUSKuqi_R37MhAVDnEf_UljVfjzqGyxdaJC3oTQms-EYiXPertk",
+        "dq": "KTeZFnOZaEe5C-sVxYMEhejB4y7xLD0EkEsD4gsuXAdCWLw2Yw3JNzz_VNjAz9RwcI1z_mGLBeczKStrjFFKZTKp0vp446s_6SbUy6fna-01Mhw5-byUUgudsbEk3fh2QJwPoQqmETLyKWBLT3JhyaQM4y5y-bWb9Itqf_B8LcE",
+        "qi": "txepV7q5_DEv0ZkC1Vm4Zalvt6NGlL0K45dJNUMCAY44_xZh4noQm3FthjNYUgZiZYR-bHN0FXQVLCXr4V0T6_rGysVGI99_T4SOz8fWoih3uXRpP3pZB506rL5dOkIJ8eT74CVYL3M8LGG7byHyPY9HPJRC1ZQjgLL5EaVdChk"
+    }
+"#;
+/// Encrypted with `alg: RSA-OAEP-256`, `enc: A256GCM` and `zip: DEF` for [`RSA_PRIVATE_JWK`].
+const RSA_OAEP_TOKEN: &str = "eyJhbGciOiJSU0EtT0FFUC0yNTYiLCJlbmMiOiJBMjU2R0NNIiwiemlwIjoiREVGIn0\
+    .qzktB6LGsnxEbkPa5REDlc2aRc4WL9fK2W2FXWL2fI3HhzAOpRVTCcH--sDRk3JsO7x6F2yfRJVrPyVcfUfdrfGXoae\
+    jw3ZvKZXvzi5_ns0smKKfVqgexcEh62eJZ8HQPZ5RfOrfvdE9adeSQ_-1_bVHaMsISA2xUPWdGvMHqPAmeFdBXg3lSWm\
+    HMT0Bq00-Nj6R4yy2dsbEtMRfPZRyAOB9wLV8pH31mOcEhEzkGNRbpxOmPKM0c6ZgSqFxA-uSyrrwuPwgTbgUpTueVu8\
+    WrtB7tOxNoKY3ZBsKdTuyMcQ9tO-z4yNK60sNBr7nRLHQFJk_C0-ydYBGhBNcmjSWcA.AuR7cwTW0yIYwtf6.xGLxRL6\
+    EUDz8Q1UCJv88kc1N55zMqfy3CitjuB5btta-lxs-6r6n.2sccP-PF7MmG4tSOT_7ppw";
+/// Encrypted with `alg: ECDH-ES+A256KW`, `enc: A128CBC-HS256` for [`P256_PRIVATE_JWK`].
+/// The payload is a token signed with ES256 using the same key.
+const ECDH_ES_TOKEN: &str = "eyJhbGciOiJFQ0RILUVTK0EyNTZLVyIsImVuYyI6IkExMjhDQkMtSFMyNTYiLCJlcGsiOnsia3R5IjoiRUMiLCJjcnYiOiJQLTI1NiIsIngiOiJ5MkdoOXdWYWoyVGhsRlZ3TlIxb1JTTXVsdXY4eFhNNlh3RmJqSEtUV2JnIiwieSI6InhYZkRsQUhzLXRJRXplNU5fX0dzUjlkVzlrV2sydkhuTWRQb3JUQmx1RlUifSwiY3R5IjoiSldUIn0\
+    .TWcZEFaOtqlsXjtCT5xuOYiiQSireU6ogBYyItgQ6akVOz34gjT1Rw.amdgg2t2ZDVzLSycBAFfcw.DVw6ZUNE1M5V2\
+    uTHwlPeeLbyw_KyFuvHlYs2rbzp62vNcMyButlPNTGgRQ6-m9kPGYz9-JQFW-LRsvEEmYTSsF93_Ult4vXF1OiKcx4Wv\
+    r53IMTgw5p-566TeHqwmeTpy3i8D38MEh1IcK8aEdWXku2Yi23cMX0FyfcQdmbPTAXfXc0QqBAY4nSrfNrkLZhYSlg0I\
+    G3cVDmo_Tph6hYjyh0597Q44vv1lFlzhOxizksks-ID9jXdzx0mvUCKZyT6.MgDE8ENS2VrzAHThKsPhNQ";
+
 struct TestRigBase<C: Component> {
     root_element: Element,
     component: AppHandle<C>,
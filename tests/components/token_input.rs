@@ -2,48 +2,76 @@
 
 // TODO: test state persistence
 
+use assert_matches::assert_matches;
+use gloo_timers::future::sleep;
 use jwt_compact::UntrustedToken;
-use wasm_bindgen::UnwrapThrowExt;
+use wasm_bindgen::{JsCast, UnwrapThrowExt};
 use wasm_bindgen_test::*;
-use web_sys::Element;
+use web_sys::{Element, HtmlElement};
 use yew::Callback;
 
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
 
-use super::{extract_feedback, extract_main_value, extract_rows, TestRigBase, HS256_TOKEN};
-use justwebtoken_io::components::token_input::{
-    TokenInput, TokenInputMessage, TokenInputProperties,
+use super::{
+    extract_feedback, extract_main_value, extract_rows, p256_public_jwk, select_elements,
+    select_single_element, sign_p256_token, token_with_certificate_chain, token_with_embedded_key,
+    TestRigBase, CRITICAL_HEADER_TOKEN, DETACHED_TOKEN, DETACHED_TOKEN_PAYLOAD,
+    FLATTENED_JSON_TOKEN, GENERAL_JSON_TOKEN, HS256_TOKEN, NESTED_JWE_TOKEN, NESTED_JWS_TOKEN,
+    UNENCODED_TOKEN, X5C_MISMATCHED_ALGORITHM_CHAIN, X5C_OTHER_ROOT_CERTIFICATE,
+    X5C_P256_SHA384_CHAIN, X5C_ROOT_CERTIFICATE,
+};
+use justwebtoken_io::{
+    components::token_input::{InputToken, TokenInput, TokenInputMessage, TokenInputProperties},
+    jwe::EncryptedToken,
+    keys::GenericHeader,
 };
 
 struct TestRig {
     base: TestRigBase<TokenInput>,
-    received_token: Rc<RefCell<Option<UntrustedToken<'static>>>>,
+    received_token: Rc<RefCell<Option<InputToken>>>,
+    received_embedded_key: Rc<RefCell<Option<String>>>,
 }
 
 impl TestRig {
     fn new() -> Self {
         let received_token = Rc::new(RefCell::new(None));
         let received_token_ = Rc::clone(&received_token);
+        let received_embedded_key = Rc::new(RefCell::new(None));
+        let received_embedded_key_ = Rc::clone(&received_embedded_key);
         let props = TokenInputProperties {
             onchange: Callback::from(move |token| {
                 *received_token_.borrow_mut() = token;
             }),
+            onembeddedkey: Callback::from(move |jwk| {
+                *received_embedded_key_.borrow_mut() = Some(jwk);
+            }),
             ..TokenInputProperties::default()
         };
 
         Self {
             base: TestRigBase::new(props),
             received_token,
+            received_embedded_key,
         }
     }
 
-    fn take_received_token(&self) -> UntrustedToken<'static> {
+    fn take_received_input_token(&self) -> InputToken {
         self.received_token
             .borrow_mut()
             .take()
             .expect_throw("no token received")
     }
 
+    fn take_received_token(&self) -> UntrustedToken<'static> {
+        let token = self.take_received_input_token();
+        assert_matches!(token, InputToken::Signed(token) => token)
+    }
+
+    fn take_received_encrypted_token(&self) -> EncryptedToken {
+        let token = self.take_received_input_token();
+        assert_matches!(token, InputToken::Encrypted(token) => token)
+    }
+
     fn assert_no_received_token(&self) {
         if let Some(token) = &*self.received_token.borrow() {
             panic!("Unexpected received token: {token:?}");
@@ -72,6 +100,214 @@ async fn correct_token() {
     assert_eq!(typ, "JWT");
 }
 
+#[wasm_bindgen_test]
+async fn all_header_fields_are_displayed() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(
+            CRITICAL_HEADER_TOKEN.to_owned(),
+        ))
+        .await;
+
+    let rows = rig.rows();
+    let alg = extract_main_value(&rows["Algorithm"]);
+    assert_eq!(alg, "HS256");
+    let critical = extract_main_value(&rows["Critical headers"]);
+    assert_eq!(critical, "exp");
+    let exp = extract_main_value(&rows["exp"]);
+    assert_eq!(exp, "1363284000");
+    let vendor_field = &rows["x-vendor"];
+    assert_eq!(extract_main_value(vendor_field), r#"{"id":1}"#);
+
+    let raw_header = select_single_element(&rig.base.root_element, "#raw-header-content");
+    let raw_header: serde_json::Value =
+        serde_json::from_str(&raw_header.text_content().unwrap()).unwrap();
+    assert_eq!(raw_header["x-vendor"]["id"], 1);
+    assert_eq!(raw_header["crit"][0], "exp");
+}
+
+#[wasm_bindgen_test]
+async fn embedded_key_is_detected() {
+    let rig = TestRig::new();
+    let (token, jwk) = token_with_embedded_key();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token))
+        .await;
+
+    let rows = rig.rows();
+    let embedded_key = rows["Embedded key"].text_content().unwrap();
+    assert!(
+        embedded_key.contains("Elliptic curve (P-256)"),
+        "{embedded_key}"
+    );
+    assert!(
+        embedded_key.contains("dRoPmbDTZoVdeNI83yO-DOt1AAxUuETqAgz6ZC5ut90"),
+        "{embedded_key}"
+    );
+    assert!(
+        embedded_key.contains("not who has issued it"),
+        "{embedded_key}"
+    );
+    assert!(rig.received_embedded_key.borrow().is_none());
+
+    let button = select_single_element(&rig.base.root_element, "#use-embedded-key");
+    button.dyn_into::<HtmlElement>().unwrap().click();
+    sleep(Duration::ZERO).await;
+
+    let received_key = rig.received_embedded_key.borrow_mut().take().unwrap();
+    let received_key: serde_json::Value = serde_json::from_str(&received_key).unwrap();
+    assert_eq!(received_key, jwk);
+}
+
+#[wasm_bindgen_test]
+async fn certificate_chain_is_parsed() {
+    let rig = TestRig::new();
+    let token = token_with_certificate_chain();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token))
+        .await;
+
+    let rows = rig.rows();
+    let chain = &rows["Key certificate chain"];
+    let certificates: Vec<_> = select_elements(chain, ".x5c-certificate")
+        .map(|cert| cert.text_content().unwrap())
+        .collect();
+    assert_eq!(certificates.len(), 2);
+    assert!(
+        certificates[0].contains("Subject: CN=idp.example.com,O=Example Corp"),
+        "{certificates:?}"
+    );
+    assert!(
+        certificates[0].contains("Issuer: CN=Example Issuing CA,O=Example Corp"),
+        "{certificates:?}"
+    );
+    assert!(
+        certificates[0].contains("Valid: 2020-01-01 00:00:00 UTC – 2099-12-31 00:00:00 UTC"),
+        "{certificates:?}"
+    );
+    assert!(
+        certificates[0].contains("Key: Elliptic curve (P-256)"),
+        "{certificates:?}"
+    );
+    assert!(
+        certificates[1].contains("Key: Elliptic curve (P-384)"),
+        "{certificates:?}"
+    );
+
+    let validation = select_single_element(chain, "#x5c-validation.text-warning-emphasis");
+    let validation = validation.text_content().unwrap();
+    assert!(validation.contains("internally consistent"), "{validation}");
+
+    let thumbprint = rows["Key certificate thumbprint"].text_content().unwrap();
+    assert!(
+        thumbprint.contains("Matches the leaf certificate"),
+        "{thumbprint}"
+    );
+
+    let button = select_single_element(chain, "#use-x5c-key");
+    button.dyn_into::<HtmlElement>().unwrap().click();
+    sleep(Duration::ZERO).await;
+    let received_key = rig.received_embedded_key.borrow_mut().take().unwrap();
+    let received_key: serde_json::Value = serde_json::from_str(&received_key).unwrap();
+    assert_eq!(received_key, p256_public_jwk());
+}
+
+#[wasm_bindgen_test]
+async fn certificate_chain_is_validated_against_root() {
+    let rig = TestRig::new();
+    let token = token_with_certificate_chain();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token))
+        .await;
+    rig.base
+        .send_message(TokenInputMessage::SetRootCertificate(
+            X5C_ROOT_CERTIFICATE.to_owned(),
+        ))
+        .await;
+
+    let validation = select_single_element(&rig.base.root_element, "#x5c-validation.text-success");
+    let validation = validation.text_content().unwrap();
+    assert!(
+        validation.contains("issued by the provided root certificate"),
+        "{validation}"
+    );
+
+    rig.base
+        .send_message(TokenInputMessage::SetRootCertificate(
+            X5C_OTHER_ROOT_CERTIFICATE.to_owned(),
+        ))
+        .await;
+    let validation = select_single_element(&rig.base.root_element, "#x5c-validation.text-danger");
+    let validation = validation.text_content().unwrap();
+    assert!(
+        validation.contains("signature of certificate #2 cannot be verified"),
+        "{validation}"
+    );
+
+    rig.base
+        .send_message(TokenInputMessage::SetRootCertificate("??".to_owned()))
+        .await;
+    let validation = select_single_element(&rig.base.root_element, "#x5c-validation");
+    let validation = validation.text_content().unwrap();
+    assert!(
+        validation.contains("invalid root certificate"),
+        "{validation}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn unsupported_certificate_hash_and_curve_combination_is_reported() {
+    let rig = TestRig::new();
+    let mut fields = serde_json::Map::new();
+    fields.insert("x5c".to_owned(), serde_json::json!(X5C_P256_SHA384_CHAIN));
+    let token = sign_p256_token(
+        GenericHeader::new(fields),
+        serde_json::json!({ "sub": "bob" }),
+    );
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token))
+        .await;
+
+    let validation = select_single_element(&rig.base.root_element, "#x5c-validation.text-danger");
+    let validation = validation.text_content().unwrap();
+    assert!(
+        validation.contains("signature of certificate #1 cannot be verified"),
+        "{validation}"
+    );
+    assert!(
+        validation.contains("unsupported hash/curve combination: ECDSA with SHA-384 by a P-256"),
+        "{validation}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn certificate_with_mismatched_signature_algorithms_is_rejected() {
+    let rig = TestRig::new();
+    let mut fields = serde_json::Map::new();
+    fields.insert(
+        "x5c".to_owned(),
+        serde_json::json!(X5C_MISMATCHED_ALGORITHM_CHAIN),
+    );
+    let token = sign_p256_token(
+        GenericHeader::new(fields),
+        serde_json::json!({ "sub": "bob" }),
+    );
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token))
+        .await;
+
+    let validation = select_single_element(&rig.base.root_element, "#x5c-validation.text-danger");
+    let validation = validation.text_content().unwrap();
+    assert!(
+        validation.contains("signature of certificate #1 cannot be verified"),
+        "{validation}"
+    );
+    assert!(
+        validation.contains("signature algorithm does not match"),
+        "{validation}"
+    );
+}
+
 #[wasm_bindgen_test]
 async fn incorrect_token_serialization() {
     let rig = TestRig::new();
@@ -87,3 +323,200 @@ async fn incorrect_token_serialization() {
     let feedback = extract_feedback(&rows["Token"]);
     assert!(feedback.contains("Error deserializing token"), "{feedback}");
 }
+
+#[wasm_bindgen_test]
+async fn encrypted_token() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(NESTED_JWE_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_encrypted_token();
+    assert_eq!(received_token.header().algorithm, "dir");
+    assert_eq!(received_token.header().encryption, "A128CBC-HS256");
+
+    let rows = rig.rows();
+    let alg = extract_main_value(&rows["Algorithm"]);
+    assert_eq!(alg, "dir");
+    let enc = extract_main_value(&rows["Content encryption"]);
+    assert_eq!(enc, "A128CBC-HS256");
+    let cty = extract_main_value(&rows["Content type"]);
+    assert_eq!(cty, "JWT");
+    assert!(!rows.contains_key("Compression"), "{rows:?}");
+}
+
+#[wasm_bindgen_test]
+async fn nested_signed_token() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(NESTED_JWS_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Nested(token) => token);
+    assert_eq!(received_token.algorithm(), "HS256");
+    assert_eq!(received_token.header().key_id.as_deref(), Some("key-1"));
+    let payload = std::str::from_utf8(received_token.payload()).unwrap();
+    let inner_token = UntrustedToken::new(payload).unwrap();
+    assert_eq!(inner_token.header().key_id.as_deref(), Some("key-0"));
+
+    let rows = rig.rows();
+    let alg = extract_main_value(&rows["Algorithm"]);
+    assert_eq!(alg, "HS256");
+    let cty = extract_main_value(&rows["Content type"]);
+    assert_eq!(cty, "JWT");
+}
+
+#[wasm_bindgen_test]
+async fn token_in_general_json_serialization() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(GENERAL_JSON_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Json(token) => token);
+    assert!(!received_token.is_flattened());
+    let signatures = received_token.signatures();
+    assert_eq!(signatures.len(), 2);
+    assert_eq!(signatures[0].algorithm(), "HS256");
+    assert_eq!(signatures[0].key_id(), Some("key-0"));
+    assert_eq!(signatures[1].algorithm(), "ES256");
+    assert_eq!(signatures[1].key_id(), Some("p256"));
+
+    let rows = rig.rows();
+    let serialization = extract_main_value(&rows["Serialization"]);
+    assert_eq!(serialization, "JSON (general)");
+    let signature = rows["Signature #2"].text_content().unwrap();
+    assert!(signature.contains("ES256"), "{signature}");
+    assert!(signature.contains("Protected header"), "{signature}");
+    assert!(signature.contains("Unprotected header"), "{signature}");
+}
+
+#[wasm_bindgen_test]
+async fn token_in_flattened_json_serialization() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(FLATTENED_JSON_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Json(token) => token);
+    assert!(received_token.is_flattened());
+    assert_eq!(received_token.signatures().len(), 1);
+
+    let rows = rig.rows();
+    let serialization = extract_main_value(&rows["Serialization"]);
+    assert_eq!(serialization, "JSON (flattened)");
+    assert!(rows.contains_key("Signature #1"), "{rows:?}");
+    assert!(!rows.contains_key("Signature #2"), "{rows:?}");
+}
+
+#[wasm_bindgen_test]
+async fn incorrect_json_serialization() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(
+            r#"{ "payload": "e30" }"#.to_owned(),
+        ))
+        .await;
+
+    rig.assert_no_received_token();
+    let rows = rig.rows();
+    let feedback = extract_feedback(&rows["Token"]);
+    assert!(
+        feedback.contains("`signature` field is missing"),
+        "{feedback}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn token_with_detached_payload() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(DETACHED_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Detached(token) => token);
+    assert!(received_token.is_detached());
+    assert!(!received_token.is_payload_encoded());
+    assert_eq!(received_token.payload(), None);
+
+    let rows = rig.rows();
+    assert!(rows.contains_key("Detached payload"), "{rows:?}");
+    let encoding = extract_main_value(&rows["Payload encoding"]);
+    assert_eq!(encoding, "false");
+    let critical = extract_main_value(&rows["Critical headers"]);
+    assert_eq!(critical, "b64");
+
+    rig.base
+        .send_message(TokenInputMessage::SetDetachedPayload(
+            DETACHED_TOKEN_PAYLOAD.to_owned(),
+        ))
+        .await;
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Detached(token) => token);
+    assert_eq!(
+        received_token.payload(),
+        Some(DETACHED_TOKEN_PAYLOAD.as_bytes())
+    );
+}
+
+#[wasm_bindgen_test]
+async fn token_with_unencoded_payload() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken(UNENCODED_TOKEN.to_owned()))
+        .await;
+
+    let received_token = rig.take_received_input_token();
+    let received_token = assert_matches!(received_token, InputToken::Detached(token) => token);
+    assert!(!received_token.is_detached());
+    assert_eq!(
+        received_token.payload(),
+        Some(DETACHED_TOKEN_PAYLOAD.as_bytes())
+    );
+
+    let rows = rig.rows();
+    assert!(!rows.contains_key("Detached payload"), "{rows:?}");
+    let encoding = extract_main_value(&rows["Payload encoding"]);
+    assert_eq!(encoding, "false");
+}
+
+#[wasm_bindgen_test]
+async fn unencoded_payload_without_critical_header() {
+    let rig = TestRig::new();
+    // `{"alg":"HS256","b64":false}`
+    let token = "eyJhbGciOiJIUzI1NiIsImI2NCI6ZmFsc2V9..\
+        A5dxf2s96_n5FLueVuW1Z_vh161FwXZC4YLPff6dmDY";
+    rig.base
+        .send_message(TokenInputMessage::SetToken(token.to_owned()))
+        .await;
+
+    rig.assert_no_received_token();
+    let rows = rig.rows();
+    let feedback = extract_feedback(&rows["Token"]);
+    assert!(
+        feedback.contains("`b64` header must be listed in `crit`"),
+        "{feedback}"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn incorrect_encrypted_token_serialization() {
+    let rig = TestRig::new();
+    rig.base
+        .send_message(TokenInputMessage::SetToken("e30.a.b.c.d".to_owned()))
+        .await;
+
+    rig.assert_no_received_token();
+
+    let rows = rig.rows();
+    assert!(!rows.contains_key("Algorithm"), "{rows:?}");
+    let feedback = extract_feedback(&rows["Token"]);
+    assert!(
+        feedback.contains("`alg` header field is missing"),
+        "{feedback}"
+    );
+}
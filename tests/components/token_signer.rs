@@ -0,0 +1,122 @@
+//! Tests for `<TokenSigner />` component.
+
+use jwt_compact::UntrustedToken;
+use wasm_bindgen_test::*;
+use web_sys::Element;
+
+use std::collections::HashMap;
+
+use super::{
+    extract_feedback, extract_main_value, extract_rows, select_single_element, TestRigBase,
+    HS256_JWK, P256_JWK, P256_PRIVATE_JWK,
+};
+use justwebtoken_io::{
+    components::token_signer::{TokenSigner, TokenSignerMessage, TokenSignerProperties},
+    keys::KeyInstance,
+};
+
+type TestRig = TestRigBase<TokenSigner>;
+
+fn rows(rig: &TestRig) -> HashMap<String, Element> {
+    extract_rows(&rig.root_element)
+}
+
+fn created_token(rig: &TestRig) -> UntrustedToken<'static> {
+    let token = select_single_element(&rig.root_element, "#created-token");
+    let token = token.text_content().unwrap();
+    UntrustedToken::new(&token).unwrap().into_owned()
+}
+
+fn verifying_key(jwk: &str) -> KeyInstance {
+    let jwk = serde_json::from_str(jwk).unwrap();
+    KeyInstance::new(&jwk).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn creating_token_with_symmetric_key() {
+    let rig = TestRig::new(TokenSignerProperties::default());
+    rig.send_message(TokenSignerMessage::SetClaims(
+        r#"{ "iss": "joe", "exp": 1300819380 }"#.to_owned(),
+    ))
+    .await;
+    rig.send_message(TokenSignerMessage::SetKey(HS256_JWK.to_owned()))
+        .await;
+
+    let rows = rows(&rig);
+    assert_eq!(extract_main_value(&rows["Algorithm"]), "HS256");
+    let key_id = extract_main_value(&rows["Key ID"]);
+    assert_eq!(key_id, "y_x3gCJnL6oKGBBIXScabduwxTVy2Wd2bzRVEUbdUzc");
+
+    let token = created_token(&rig);
+    assert_eq!(token.algorithm(), "HS256");
+    assert_eq!(token.header().key_id.as_deref(), Some(key_id.as_str()));
+    assert_eq!(token.header().token_type.as_deref(), Some("JWT"));
+    let token = verifying_key(HS256_JWK).verify_token(&token).unwrap();
+    assert_eq!(token.claims().custom["iss"], "joe");
+    assert_eq!(
+        token.claims().expiration.unwrap().timestamp(),
+        1_300_819_380
+    );
+}
+
+#[wasm_bindgen_test]
+async fn algorithm_and_key_id_can_be_overridden() {
+    let rig = TestRig::new(TokenSignerProperties::default());
+    rig.send_message(TokenSignerMessage::SetHeader(
+        r#"{ "alg": "HS512", "kid": "my-key" }"#.to_owned(),
+    ))
+    .await;
+    rig.send_message(TokenSignerMessage::SetKey(HS256_JWK.to_owned()))
+        .await;
+
+    let token = created_token(&rig);
+    assert_eq!(token.algorithm(), "HS512");
+    assert_eq!(token.header().key_id.as_deref(), Some("my-key"));
+    verifying_key(HS256_JWK).verify_token(&token).unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn creating_token_with_ecdsa_key() {
+    let rig = TestRig::new(TokenSignerProperties::default());
+    rig.send_message(TokenSignerMessage::SetKey(P256_PRIVATE_JWK.to_owned()))
+        .await;
+
+    let rows = rows(&rig);
+    assert_eq!(extract_main_value(&rows["Algorithm"]), "ES256");
+    let key_id = extract_main_value(&rows["Key ID"]);
+    assert_eq!(key_id, "dRoPmbDTZoVdeNI83yO-DOt1AAxUuETqAgz6ZC5ut90");
+    let token = created_token(&rig);
+    verifying_key(P256_PRIVATE_JWK)
+        .verify_token(&token)
+        .unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn error_for_public_key() {
+    let rig = TestRig::new(TokenSignerProperties::default());
+    rig.send_message(TokenSignerMessage::SetKey(P256_JWK.to_owned()))
+        .await;
+
+    let rows = rows(&rig);
+    let feedback = extract_feedback(&rows["Signing key"]);
+    assert_eq!(feedback, "key does not contain private parts");
+    assert!(!rows.contains_key("Algorithm"), "{rows:?}");
+}
+
+#[wasm_bindgen_test]
+async fn error_for_incompatible_algorithm() {
+    let rig = TestRig::new(TokenSignerProperties::default());
+    rig.send_message(TokenSignerMessage::SetHeader(
+        r#"{ "alg": "RS256" }"#.to_owned(),
+    ))
+    .await;
+    rig.send_message(TokenSignerMessage::SetKey(P256_PRIVATE_JWK.to_owned()))
+        .await;
+
+    let alert = select_single_element(&rig.root_element, ".card-alert.border-danger");
+    let alert_text = alert.text_content().unwrap();
+    assert!(
+        alert_text.contains("token algorithm (RS256) is not compatible with the key"),
+        "{alert_text}"
+    );
+}
@@ -14,7 +14,6 @@ function requireToml(pathToToml) {
   return toml.parse(fs.readFileSync(pathToToml, { encoding: 'utf-8' }));
 }
 
-const standardFields = requireToml('./src/fields.toml');
 const cargoLockfile = requireToml('./Cargo.lock');
 const distPath = path.resolve(__dirname, 'dist');
 
@@ -68,7 +67,6 @@ const htmlPlugins = Object.keys(entries).map((entry) => new HtmlWebpackPlugin({
   template: `webpack/templates/${entry}.pug`,
   templateParameters: {
     $pages: pages,
-    $standardFields: standardFields,
     $buildInfo: buildInfo,
   },
 }));